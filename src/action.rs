@@ -5,20 +5,20 @@ use std::{
 use hex::Hex;
 use serde::{Deserialize, Serialize};
 use ts_data_derive::TsData;
-use wasm_bindgen::prelude::wasm_bindgen;
 
 use crate::{
     id_map::{Id},
     card::Card,
     creature::Creature,
     error::Error,
+    map::Object,
     part::{Part, PartTag, TagMod, TagModId},
     serde_empty,
     status::{Status, StatusId},
     world::World,
 };
 
-#[derive(Debug, Clone, Serialize)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Meta<T> {
     pub source: Path,
     pub target: Path,
@@ -53,6 +53,7 @@ pub enum Path {
     Creature { cid: Id<Creature> },
     Part { cid: Id<Creature>, pid: Id<Part> },
     Card { cid: Id<Creature>, pid: Id<Part>, card: Id<Card> },
+    Hex { hex: Hex },
 }
 
 impl Path {
@@ -73,18 +74,30 @@ impl Path {
         }
     }
 
+    /// Where this is; for creatures (and their parts and cards), the hex the
+    /// creature stands on.
     pub fn hex(&self, world: &World) -> Option<Hex> {
+        if let Path::Hex { hex } = self { return Some(*hex); }
         self.creature().and_then(|cid| world.map().creatures().get(&cid).cloned())
     }
+
+    /// Every hex this covers, which for big creatures is more than one.
+    pub fn hexes(&self, world: &World) -> Vec<Hex> {
+        match (self, self.creature()) {
+            (Path::Hex { hex }, _) => vec![*hex],
+            (_, Some(cid)) => world.map().occupied(cid),
+            _ => vec![],
+        }
+    }
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, TsData)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TsData)]
 pub enum Tag {
     Attack,
     NoRender,
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum ActionData {
     // Special
     Nothing,
@@ -105,6 +118,10 @@ pub enum ActionData {
     // Card
     Discard,
 
+    // Hex
+    // Whoever's on the hex uses the object there; see `Map::enter_object`.
+    Interact,
+
     // Part
     Hit { damage: i32 },
     Heal { hp: i32 },
@@ -112,6 +129,10 @@ pub enum ActionData {
     ClearTags { tags: Vec<PartTag> },
     AddTagMod { m: TagMod },
     ClearTagMod { id: TagModId, },
+    // Removes the part and everything attached to it.
+    Sever,
+    // Target is the parent part.
+    Attach { part: Part, joint: usize },
 }
 
 pub mod action {
@@ -158,6 +179,15 @@ pub enum EventData {
     TagsCleared { tags: Vec<PartTag> },
     TagsModded { id: TagModId },
     TagsUnmodded { id: TagModId },
+    #[serde(with = "serde_empty")]
+    Severed,
+    Attached { parent: Id<Part> },
+
+    // Hex
+    ObjectHit { hp: i32 },
+    #[serde(with = "serde_empty")]
+    ObjectDestroyed,
+    ObjectUsed { object: Object },
 
     // Cosmetic
     FloatText { text: String },
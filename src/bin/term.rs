@@ -0,0 +1,368 @@
+//! A headless terminal client for playing a single encounter.
+
+use std::{
+    collections::HashSet,
+    io::{self, BufRead, Write},
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use hex::Hex;
+
+use rs_parts_unknown::{
+    action::{Event, EventData, Path},
+    card::Card,
+    command::Command,
+    creature::Creature,
+    id_map::Id,
+    library,
+    map::{Fov, Space},
+    map_file,
+    part::{Part, PartTag},
+    world::{self, GameState, World},
+};
+
+const HELP: &str = "\
+Commands:
+  move X Y          move the player to hex (X, Y)
+  targets N [T...]  list valid next targets for card N after picks T...
+  play N [T...]     play card N in hand on targets T... (from `targets`)
+  end               end the turn
+  show              redraw the map and hand
+  fov shadow|dual   switch line of sight to shadowcasting or dual lines
+  help              show this message
+  quit              exit";
+
+fn main() {
+    let seed = std::env::args().nth(1)
+        .and_then(|s| s.parse().ok())
+        .unwrap_or_else(|| SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs());
+    println!("Seed: {}", seed);
+    // `debug` after the seed starts with every card.
+    let mut world = if std::env::args().nth(2).as_deref() == Some("debug") {
+        let player = library::player::debug_player();
+        World::from_encounter(seed, Some(player), world::DEFAULT_ENCOUNTER).unwrap()
+    } else {
+        World::new(seed)
+    };
+    show(&world);
+
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+    while world.state() == GameState::Play {
+        print!("> ");
+        io::stdout().flush().unwrap();
+        let line = match lines.next() {
+            Some(Ok(l)) => l,
+            _ => return,
+        };
+        let words: Vec<&str> = line.split_whitespace().collect();
+        let command = match &words as &[&str] {
+            [] => continue,
+            ["help"] => { println!("{}", HELP); continue; }
+            ["quit"] => return,
+            ["show"] => { show(&world); continue; }
+            ["fov", mode] => {
+                match *mode {
+                    "shadow" => world.set_fov(Fov::Shadowcast),
+                    "dual" => world.set_fov(Fov::DualLine),
+                    _ => { println!("Unknown line of sight mode."); continue; }
+                }
+                show(&world);
+                continue;
+            }
+            ["targets", n, picks @ ..] => {
+                match (n.parse(), pick_targets(&world, n, picks)) {
+                    (Ok(ix), Some(prior)) => print_targets(&world, ix, &prior),
+                    _ => println!("Invalid card or target number."),
+                }
+                continue;
+            }
+            ["move", x, y] => match (x.parse(), y.parse()) {
+                (Ok(x), Ok(y)) => Command::MovePlayer { to: Hex { x, y } },
+                _ => { println!("Invalid hex."); continue; }
+            }
+            ["play", n, picks @ ..] => {
+                let hand_ix = match n.parse() {
+                    Ok(ix) => ix,
+                    Err(_) => { println!("Invalid card number."); continue; }
+                };
+                let mut targets = match pick_targets(&world, n, picks) {
+                    Some(t) => t,
+                    None => { println!("Invalid target number."); continue; }
+                };
+                // Fill in any remaining steps that have only the one choice.
+                loop {
+                    let next = card_targets(&world, hand_ix, &targets);
+                    if next.len() != 1 { break; }
+                    targets.extend(next);
+                }
+                Command::PlayCard { hand_ix, targets }
+            }
+            ["end"] => Command::EndTurn,
+            _ => { println!("Unknown command; try `help`."); continue; }
+        };
+        if let Err(e) = world.validate(&command) {
+            println!("Can't do that: {}", e);
+            continue;
+        }
+        let before = world.clone();
+        let events = world.apply(command);
+        for event in &events {
+            if let Some(line) = describe(&before, &world, event) {
+                println!("  {}", line);
+            }
+        }
+        show(&world);
+    }
+    match world.state() {
+        GameState::Won => {
+            println!("You won!");
+            choose_reward(&mut world, &mut lines);
+        }
+        GameState::Lost => println!("You lost!"),
+        GameState::Play => (),
+    }
+}
+
+fn choose_reward<I: Iterator<Item = io::Result<String>>>(world: &mut World, lines: &mut I) {
+    let player = world.creatures().get(world.player_id()).unwrap();
+    let mut options = vec![];
+    for reward in world.rewards() {
+        let creature = world.creatures().get(reward.cid).unwrap();
+        let part = &creature.parts.get(reward.pid).unwrap().name;
+        for socket in &reward.sockets {
+            let parent = &player.parts.get(socket.pid).unwrap().name;
+            let mut label = format!("take {}'s {} onto your {}", creature.name, part, parent);
+            if let Some(old) = socket.replaces {
+                label.push_str(&format!(" (replacing {})", player.parts.get(old).unwrap().name));
+            }
+            let command = Command::Harvest {
+                cid: reward.cid, pid: reward.pid, parent: socket.pid, joint: socket.joint,
+            };
+            options.push((label, command));
+        }
+    }
+    if options.is_empty() { return; }
+    println!("Choose a reward (anything else skips):");
+    for (ix, (label, _)) in options.iter().enumerate() {
+        println!("  {}: {}", ix, label);
+    }
+    print!("> ");
+    io::stdout().flush().unwrap();
+    let line = match lines.next() {
+        Some(Ok(l)) => l,
+        _ => return,
+    };
+    let command = match line.trim().parse::<usize>().ok().and_then(|ix| options.get(ix)) {
+        Some((_, c)) => c.clone(),
+        None => return,
+    };
+    let before = world.clone();
+    let events = world.apply(command);
+    for event in &events {
+        if let Some(line) = describe(&before, world, event) {
+            println!("  {}", line);
+        }
+    }
+    print_creatures(world);
+}
+
+fn show(world: &World) {
+    draw_map(world);
+    print_creatures(world);
+    print_hand(world);
+}
+
+fn draw_map(world: &World) {
+    let map = world.map();
+    let player_id = world.player_id();
+    let los: HashSet<Hex> = world.visible_to(player_id);
+    let npcs = npc_ids(world);
+    let (min_y, max_y) = bounds(map.tiles().keys().map(|h| h.y));
+    let (min_col, max_col) = bounds(map.tiles().keys().map(|h| 2*h.x + h.y));
+    for y in min_y..=max_y {
+        let mut row = String::new();
+        for col in min_col..=max_col {
+            // Axial to doubled-width columns: only cells with matching parity
+            // are hexes.
+            if (col - y).rem_euclid(2) != 0 {
+                row.push(' ');
+                continue;
+            }
+            let hex = Hex { x: (col - y) / 2, y };
+            let tile = match map.tiles().get(&hex) {
+                Some(t) if world.known().contains(&hex) => t,
+                _ => { row.push(' '); continue; }
+            };
+            let creature = tile.creature.filter(|_| los.contains(&hex));
+            let ch = match (creature, tile.space) {
+                (Some(id), _) if id == player_id => '@',
+                (Some(id), _) => npc_char(&npcs, id, world),
+                (None, _) if tile.object.is_some() => map_file::object_symbol(tile.object.as_ref().unwrap()),
+                (None, Space::Wall) => '#',
+                (None, Space::Empty) if los.contains(&hex) => '.',
+                (None, Space::Empty) => ':',
+                (None, Space::Rough) => '%',
+                (None, Space::Water) => '~',
+                (None, Space::Pit) => 'O',
+                (None, Space::Fire) => '^',
+            };
+            row.push(ch);
+        }
+        println!("{}", row.trim_end());
+    }
+}
+
+fn bounds<I: Iterator<Item=i32>>(values: I) -> (i32, i32) {
+    values.fold((std::i32::MAX, std::i32::MIN), |(lo, hi), v| (lo.min(v), hi.max(v)))
+}
+
+fn npc_ids(world: &World) -> Vec<Id<Creature>> {
+    world.creatures().keys().cloned().filter(|&id| id != world.player_id()).collect()
+}
+
+fn npc_char(npcs: &[Id<Creature>], id: Id<Creature>, world: &World) -> char {
+    if world.creatures().get(id).map_or(false, |c| c.dead) { return '%'; }
+    let ix = npcs.iter().position(|&n| n == id).unwrap_or(0);
+    (b'a' + (ix % 26) as u8) as char
+}
+
+fn print_creatures(world: &World) {
+    let npcs = npc_ids(world);
+    for (&id, creature) in world.creatures() {
+        if !world.sees(world.player_id(), id) && id != world.player_id() { continue; }
+        let label = if id == world.player_id() { '@' } else { npc_char(&npcs, id, world) };
+        let pos = world.map().creatures().get(&id)
+            .map_or("?".to_string(), |h| format!("({}, {})", h.x, h.y));
+        let mut line = format!("{} {} {} AP {} MP {}", label, creature.name, pos, creature.cur_ap, creature.cur_mp);
+        if creature.dead {
+            line.push_str(" [dead]");
+        } else if let Some(npc) = &creature.npc {
+            line.push_str(&format!(" intent: {}", npc.intent.name));
+        }
+        println!("{}", line);
+        for (_, part) in &creature.parts {
+            let mut tags: Vec<_> = part.tags().into_iter().collect();
+            tags.sort();
+            let tags: Vec<_> = tags.iter()
+                .filter(|t| matches!(t, PartTag::Open | PartTag::Broken | PartTag::Vital))
+                .map(|t| format!("{:?}", t))
+                .collect();
+            println!("    {} {}/{} {}", part.name, part.cur_hp, part.max_hp, tags.join(" "));
+        }
+    }
+}
+
+fn print_hand(world: &World) {
+    let player = world.creatures().get(world.player_id()).unwrap();
+    println!("Hand (draw {}, discard {}):", player.draw.len(), player.discard.len());
+    for (ix, &(pid, card_id)) in player.hand.iter().enumerate() {
+        let card = hand_card(world, pid, card_id);
+        let source = Path::Part { cid: world.player_id(), pid };
+        let ui = (card.ui)(world, &source, &Path::World);
+        println!("  {}: {} ({} AP) {}", ix, card.name, card.ap_cost, ui);
+    }
+}
+
+fn hand_card(world: &World, pid: Id<Part>, card_id: Id<Card>) -> &Card {
+    let player = world.creatures().get(world.player_id()).unwrap();
+    player.parts.get(pid).unwrap().cards.get(card_id).unwrap()
+}
+
+/// Resolve target numbers (as listed by `targets`) into paths, one step at a
+/// time.
+fn pick_targets(world: &World, hand_ix: &str, picks: &[&str]) -> Option<Vec<Path>> {
+    let hand_ix = hand_ix.parse().ok()?;
+    let mut prior = vec![];
+    for pick in picks {
+        let ix: usize = pick.parse().ok()?;
+        let next = card_targets(world, hand_ix, &prior);
+        prior.push(next.get(ix)?.clone());
+    }
+    Some(prior)
+}
+
+fn card_targets(world: &World, hand_ix: usize, prior: &[Path]) -> Vec<Path> {
+    let in_play = match Card::start_play(world, world.player_id(), hand_ix) {
+        Ok(ip) => ip,
+        Err(_) => return vec![],
+    };
+    let mut candidates = vec![Path::World];
+    for (&cid, creature) in world.creatures() {
+        candidates.push(Path::Creature { cid });
+        for &pid in creature.parts.keys() {
+            candidates.push(Path::Part { cid, pid });
+        }
+    }
+    let mut hexes: Vec<Hex> = world.map().tiles().keys().cloned().collect();
+    hexes.sort_by_key(|h| (h.y, h.x));
+    candidates.extend(hexes.into_iter().map(|hex| Path::Hex { hex }));
+    candidates.into_iter()
+        .filter(|t| in_play.behavior.target_valid(world, &in_play.source(), prior, t))
+        .collect()
+}
+
+fn print_targets(world: &World, hand_ix: usize, prior: &[Path]) {
+    let targets = card_targets(world, hand_ix, prior);
+    if targets.is_empty() {
+        println!("No valid targets.");
+    }
+    for (ix, target) in targets.iter().enumerate() {
+        println!("  {}: {}", ix, path_name(world, target));
+    }
+}
+
+fn path_name(world: &World, path: &Path) -> String {
+    let creature = |cid| world.creatures().get(cid).map_or("?".to_string(), |c: &Creature| c.name.clone());
+    match path {
+        Path::World => "world".into(),
+        Path::Creature { cid } => creature(*cid),
+        Path::Part { cid, pid } | Path::Card { cid, pid, .. } => {
+            let part = world.creatures().get(*cid)
+                .and_then(|c| c.parts.get(*pid))
+                .map_or("?".to_string(), |p| p.name.clone());
+            format!("{}'s {}", creature(*cid), part)
+        }
+        Path::Hex { hex } => format!("({}, {})", hex.x, hex.y),
+    }
+}
+
+/// Describe an event for the combat log; `before` is used to name things that
+/// may no longer exist in `after`.
+fn describe(before: &World, after: &World, event: &Event) -> Option<String> {
+    let target = path_name(if matches!(event.target, Path::World) { after } else { before }, &event.target);
+    let text = match &event.data {
+        EventData::Nothing | EventData::StatusAdded { .. } | EventData::StatusRemoved { .. }
+        | EventData::TagsModded { .. } | EventData::TagsUnmodded { .. } => return None,
+        EventData::Failed { description } => format!("failed: {}", description),
+        EventData::PlayerTurnEnd => "-- player turn ends --".into(),
+        EventData::NpcTurnEnd => "-- enemy turn ends --".into(),
+        EventData::Moved { to, .. } => format!("{} moves to ({}, {})", target, to.x, to.y),
+        EventData::ChangeAP { delta } => format!("{} {:+} AP", target, delta),
+        EventData::ChangeMP { delta } => format!("{} {:+} MP", target, delta),
+        EventData::Died => format!("{} dies!", target),
+        EventData::DeckRecycled => format!("{} reshuffles their discards", target),
+        EventData::Discarded => format!("{} discarded", card_name(before, &event.target)),
+        EventData::Drew => format!("drew {}", card_name(after, &event.target)),
+        EventData::ChangeHP { delta } => format!("{} {:+} HP", target, delta),
+        EventData::TagsSet { tags } => format!("{} gains {:?}", target, tags),
+        EventData::TagsCleared { tags } => format!("{} loses {:?}", target, tags),
+        EventData::Severed => format!("{} is severed", target),
+        EventData::Attached { .. } => format!("{} is attached", path_name(after, &event.target)),
+        EventData::ObjectHit { hp } => format!("{} has {} HP left", target, hp),
+        EventData::ObjectDestroyed => format!("{} is destroyed", target),
+        EventData::ObjectUsed { object } => format!("{:?} at {} is used", object, target),
+        EventData::FloatText { text } => format!("{}: {}", target, text),
+    };
+    Some(text)
+}
+
+fn card_name(world: &World, path: &Path) -> String {
+    match path {
+        Path::Card { cid, pid, card } => world.creatures().get(*cid)
+            .and_then(|c| c.parts.get(*pid))
+            .and_then(|p| p.cards.get(*card))
+            .map_or("?".to_string(), |c| c.name.clone()),
+        _ => "?".into(),
+    }
+}
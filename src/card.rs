@@ -7,7 +7,6 @@ use hex::Hex;
 use serde::{Serialize};
 use serde_json;
 use ts_data_derive::TsData;
-use wasm_bindgen::prelude::*;
 
 use crate::{
     action::{Action, Event, Path, Tag, action},
@@ -22,6 +21,8 @@ use crate::{
 
 #[derive(Clone)]
 pub struct Card {
+    // Stable id used to save and restore the card; see `Registry`.
+    pub key: &'static str,
     pub name: String,
     pub ap_cost: i32,
     // Contract: the world will not change between start_play and Behavior methods.
@@ -40,6 +41,7 @@ impl Card {
         let card = part.cards.get(card_id).ok_or(Error::NoSuchCard)?;
         let behavior = (card.start_play)(world, &Path::Part { cid: creature_id, pid: part_id });
         Ok(InPlay {
+            hand_ix,
             creature_id,
             part_id,
             card_id,
@@ -51,6 +53,7 @@ impl Card {
 
 #[derive(Clone)]
 pub struct InPlay {
+    pub hand_ix: usize,
     pub creature_id: Id<Creature>,
     pub part_id: Id<Part>,
     pub card_id: Id<Card>,
@@ -63,7 +66,7 @@ impl InPlay {
         Path::Part { cid: self.creature_id, pid: self.part_id }
     }
 
-    pub fn finish(self, world: &mut World, target: &Path) -> Vec<Event> {
+    pub fn finish(self, world: &mut World, targets: &[Path]) -> Vec<Event> {
         let mut events = world.execute(&Action {
             source: Path::World,
             target: Path::Card { cid: self.creature_id, pid: self.part_id, card: self.card_id },
@@ -80,7 +83,7 @@ impl InPlay {
         events.extend(ap);
         if !ap_failed {
             let source = Path::Part { cid: self.creature_id, pid: self.part_id };
-            events.extend(self.behavior.apply(world, source, target.clone()));
+            events.extend(self.behavior.apply(world, source, targets));
         }
         events
     }
@@ -89,6 +92,7 @@ impl InPlay {
 impl std::fmt::Debug for Card {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         f.debug_struct("Card")
+            .field("key", &self.key)
             .field("name", &self.name)
             .field("ap_cost", &self.ap_cost)
             .field("start_play", &(self.start_play as usize))
@@ -96,35 +100,40 @@ impl std::fmt::Debug for Card {
     }
 }
 
-// TASK: power scaling
+// Targets are picked in sequence; `prior` is the targets already picked, so
+// each step can depend on the ones before it.
 pub trait Behavior: BehaviorClone {
-    fn range(&self, source: &Path, world: &World) -> Vec<Hex>;
-    // TASK: allow for multiple targets
-    fn target_spec(&self) -> TargetSpec;
-    fn target_check(&self, world: &World, source: &Path, target: &Path) -> bool;
-    fn preview(&self, world: &World, source: Path, target: Path) -> Vec<Event> {
-        let mut tmp = world.clone();
-        tmp.tracer = None;
-        self.apply(&mut tmp, source, target)
+    fn target_count(&self) -> usize { 1 }
+    fn range(&self, source: &Path, world: &World, prior: &[Path]) -> Vec<Hex>;
+    fn target_spec(&self, prior: &[Path]) -> TargetSpec;
+    fn target_check(&self, world: &World, source: &Path, prior: &[Path], target: &Path) -> bool;
+    fn preview(&self, world: &World, source: Path, targets: &[Path]) -> Vec<Event> {
+        let mut tmp = world.scratch();
+        self.apply(&mut tmp, source, targets)
     }
-    fn apply(&self, world: &mut World, source: Path, target: Path) -> Vec<Event>;
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event>;
 }
 
 impl dyn Behavior {
-    pub fn target_valid(&self, world: &World, source: &Path, target: &Path) -> bool {
-        if !self.target_spec().matches(world, target) { return false; }
-        let range = self.range(source, world);
+    /// Is `target` valid as the next pick, given the `prior` picks?
+    pub fn target_valid(&self, world: &World, source: &Path, prior: &[Path], target: &Path) -> bool {
+        if prior.len() >= self.target_count() { return false; }
+        if !self.target_spec(prior).matches(world, target) { return false; }
+        let range = self.range(source, world, prior);
         if !range.is_empty() {
-            let pos = some_or!(target.hex(world), return false);
-            if !range.contains(&pos) { return false; }
+            // Big creatures can be picked by any hex they cover.
+            if !target.hexes(world).iter().any(|h| range.contains(h)) { return false; }
         }
-        self.target_check(world, source, target)
+        self.target_check(world, source, prior, target)
+    }
+
+    /// Is `targets` a complete, valid set of picks?
+    pub fn targets_valid(&self, world: &World, source: &Path, targets: &[Path]) -> bool {
+        targets.len() == self.target_count() &&
+            (0..targets.len()).all(|ix| self.target_valid(world, source, &targets[..ix], &targets[ix]))
     }
 }
 
-// TASK: multiple targets
-// May be better to just go to Parts { tags, count } rather than full
-// generic Multi(Vec<TargetSpec>)
 #[derive(Debug, Serialize, TsData)]
 pub enum TargetSpec {
     #[serde(with = "serde_empty")]
@@ -132,6 +141,10 @@ pub enum TargetSpec {
     Part { on_player: bool, tags: Vec<Vec<PartTag>> /* Or<<X and Y>, <Q and R>> */ },
     #[serde(with = "serde_empty")]
     Creature,
+    Hex { shape: HexShape },
+    /// A hex with something on it that can be hit; see `map::Object`.
+    #[serde(with = "serde_empty")]
+    Object,
 }
 
 impl TargetSpec {
@@ -150,9 +163,86 @@ impl TargetSpec {
                 false
             }
             (TargetSpec::Creature, Path::Creature { cid }) => *cid != world.player_id(),
+            (TargetSpec::Hex { .. }, Path::Hex { hex }) => world.map().tiles().contains_key(hex),
+            (TargetSpec::Object, Path::Hex { hex }) => world.map().tiles().get(hex)
+                .and_then(|t| t.object.as_ref())
+                .map_or(false, |o| o.hp().is_some()),
             _ => false,
         }
     }
+
+    /// The on-map hexes affected by picking `target`; empty for specs without
+    /// an area.
+    pub fn area(&self, world: &World, source: &Path, target: &Path) -> Vec<Hex> {
+        let shape = match self {
+            TargetSpec::Hex { shape } => shape,
+            _ => return vec![],
+        };
+        let (origin, hex) = match (source.hex(world), target) {
+            (Some(origin), Path::Hex { hex }) => (origin, *hex),
+            _ => return vec![],
+        };
+        shape.area(origin, hex).into_iter()
+            .filter(|h| world.map().tiles().contains_key(h))
+            .collect()
+    }
+}
+
+#[derive(Debug, Copy, Clone, Serialize, TsData)]
+pub enum HexShape {
+    #[serde(with = "serde_empty")]
+    Single,
+    Radius { radius: i32 },
+    /// Extends from the source through the target.
+    Line { length: i32 },
+    /// A 60 degree arc from the source, centered on the target.
+    Cone { length: i32 },
+}
+
+impl HexShape {
+    /// The covered hexes, sorted so that anything acting on them in turn does
+    /// so in the same order every time.
+    pub fn area(&self, origin: Hex, target: Hex) -> Vec<Hex> {
+        let mut out = self.unsorted_area(origin, target);
+        out.sort_by_key(|h| (h.y, h.x));
+        out
+    }
+
+    fn unsorted_area(&self, origin: Hex, target: Hex) -> Vec<Hex> {
+        match *self {
+            HexShape::Single => vec![target],
+            HexShape::Radius { radius } => target.area(radius).into_iter().collect(),
+            HexShape::Line { length } => {
+                if origin == target { return vec![]; }
+                // Scale out past the target so the line is always full length.
+                let far = Hex {
+                    x: origin.x + (target.x - origin.x) * length,
+                    y: origin.y + (target.y - origin.y) * length,
+                };
+                origin.line_to(far).skip(1).take(length as usize).collect()
+            }
+            HexShape::Cone { length } => {
+                if origin == target { return vec![]; }
+                let (dx, dy) = to_plane(origin, target);
+                let min_cos = (std::f64::consts::PI / 6.0).cos() - 1e-6;
+                origin.area(length).into_iter()
+                    .filter(|&h| {
+                        if h == origin { return false; }
+                        let (hx, hy) = to_plane(origin, h);
+                        let cos = (hx*dx + hy*dy) / ((hx*hx + hy*hy).sqrt() * (dx*dx + dy*dy).sqrt());
+                        cos >= min_cos
+                    })
+                    .collect()
+            }
+        }
+    }
+}
+
+// Offset from `origin` to `hex` in cartesian space, for angle checks.
+fn to_plane(origin: Hex, hex: Hex) -> (f64, f64) {
+    let dx = (hex.x - origin.x) as f64;
+    let dy = (hex.y - origin.y) as f64;
+    (3f64.sqrt() * (dx + dy / 2.0), 1.5 * dy)
 }
 
 pub trait BehaviorClone {
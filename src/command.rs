@@ -0,0 +1,86 @@
+use hex::Hex;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    action::{Event, Path},
+    card::{Card, InPlay},
+    creature::Creature,
+    error::{Error, Result},
+    id_map::Id,
+    part::{Part, PartTag},
+    world::{GameState, World},
+    world_ext::WorldExt,
+};
+
+/// A single player input.  All player-driven changes to a `World` should go
+/// through `World::apply`, so that they're validated consistently.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum Command {
+    MovePlayer { to: Hex },
+    PlayCard { hand_ix: usize, targets: Vec<Path> },
+    EndTurn,
+    /// Take a part from a defeated enemy; only valid once the fight is won.
+    Harvest { cid: Id<Creature>, pid: Id<Part>, parent: Id<Part>, joint: usize },
+}
+
+impl World {
+    pub fn validate(&self, command: &Command) -> Result<()> {
+        self.check(command).map(|_| ())
+    }
+
+    pub fn apply(&mut self, command: Command) -> Vec<Event> {
+        let in_play = match self.check(&command) {
+            Ok(ip) => ip,
+            Err(e) => return vec![Event::failed(e)],
+        };
+        let player_id = self.player_id();
+        let events = match (&command, in_play) {
+            (Command::MovePlayer { to }, _) => self.move_creature(player_id, *to),
+            (Command::PlayCard { targets, .. }, Some(in_play)) => in_play.finish(self, targets),
+            (Command::PlayCard { .. }, None) => vec![Event::failed(Error::NoSuchCard)],
+            (Command::EndTurn, _) => self.npc_turn(),
+            (&Command::Harvest { cid, pid, parent, joint }, _) =>
+                self.harvest(cid, pid, parent, joint).unwrap_or_else(|e| vec![Event::failed(e)]),
+        };
+        self.record(command, &events);
+        events
+    }
+
+    // Private
+
+    fn check(&self, command: &Command) -> Result<Option<InPlay>> {
+        if let &Command::Harvest { cid, pid, parent, joint } = command {
+            if !self.offers(cid, pid, parent, joint) { return Err(Error::InvalidTarget); }
+            return Ok(None);
+        }
+        if self.state() != GameState::Play { return Err(Error::GameOver); }
+        let player_id = self.player_id();
+        let player = self.creatures().get(player_id).ok_or(Error::NoSuchCreature)?;
+        match command {
+            Command::MovePlayer { to } => {
+                let path = self.map().path_for(player_id, *to)?;
+                let cost = self.map().path_cost(player_id, &path).ok_or(Error::Obstructed)?;
+                if cost > player.cur_mp {
+                    return Err(Error::NotEnough("MP".into()));
+                }
+                Ok(None)
+            }
+            Command::PlayCard { hand_ix, targets } => {
+                let in_play = Card::start_play(self, player_id, *hand_ix)?;
+                if player.cur_ap < in_play.ap_cost {
+                    return Err(Error::NotEnough("AP".into()));
+                }
+                let part = player.parts.get(in_play.part_id).ok_or(Error::NoSuchPart)?;
+                if part.tags().contains(&PartTag::Broken) {
+                    return Err(Error::BrokenPart);
+                }
+                if !in_play.behavior.targets_valid(self, &in_play.source(), targets) {
+                    return Err(Error::InvalidTarget);
+                }
+                Ok(Some(in_play))
+            }
+            Command::EndTurn => Ok(None),
+            Command::Harvest { .. } => unreachable!(),
+        }
+    }
+}
@@ -1,11 +1,16 @@
-use rand::prelude::*;
+use std::collections::HashMap;
+
+use rand::{Rng, seq::SliceRandom};
+use serde::{Deserialize, Serialize};
 
 use crate::{
-    action::{action, event, Action, Event, Path},
+    action::{action, event, Action, Event, EventData, Path},
     card::Card,
     error::{Error, Result},
     entity::Entity,
     id_map::{Id, IdMap},
+    map::Footprint,
+    mod_stack::ModStack,
     npc::{NPC},
     part::{Part, PartTag},
     some_or,
@@ -13,7 +18,7 @@ use crate::{
 
 pub type CardId = (Id<Part>, Id<Card>);
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Creature {
     pub name: String,
     pub parts: IdMap<Part>,
@@ -25,6 +30,8 @@ pub struct Creature {
     pub hand: Vec<CardId>,
     pub discard: Vec<CardId>,
     pub entity: Entity,
+    #[serde(default)]
+    pub footprint: Footprint,
 }
 
 impl Creature {
@@ -47,10 +54,11 @@ impl Creature {
             npc,
             draw: vec![], hand: vec![], discard: vec![],
             entity: Entity::new(),
+            footprint: Footprint::single(),
         };
         out.cur_ap = out.max_ap();
         out.cur_mp = out.max_mp();
-        out.reset_cards();
+        out.draw = out.all_cards();
         out
     }
 
@@ -86,9 +94,31 @@ impl Creature {
             .filter(|(_, p)| p.tags().contains(&PartTag::Open))
     }
 
+    /// The part and joint index `pid` is attached to, if any.
+    pub fn parent_of(&self, pid: Id<Part>) -> Option<(Id<Part>, usize)> {
+        self.parts.iter().find_map(|(&id, part)| {
+            part.joints.iter()
+                .position(|j| j.attached == Some(pid))
+                .map(|ix| (id, ix))
+        })
+    }
+
+    /// `pid` and everything attached below it, parents first.
+    pub fn subtree(&self, pid: Id<Part>) -> Vec<Id<Part>> {
+        let mut out = vec![pid];
+        let mut ix = 0;
+        while ix < out.len() {
+            if let Some(part) = self.parts.get(out[ix]) {
+                out.extend(part.joints.iter().filter_map(|j| j.attached));
+            }
+            ix += 1;
+        }
+        out
+    }
+
     // Mutators
 
-    pub fn resolve(&mut self, action: &Action) -> Result<Vec<Event>> {
+    pub fn resolve<R: Rng>(&mut self, action: &Action, rng: &mut R) -> Result<Vec<Event>> {
         let simple = |ev| Ok(vec![action.carry(ev)]);
         if self.dead { return Err(Error::DeadCreature); }
         match action.data {
@@ -126,7 +156,7 @@ impl Creature {
                         }
                         out.push(action.carry(event::DeckRecycled));
                         self.draw.append(&mut self.discard);
-                        self.draw.shuffle(&mut rand::thread_rng());
+                        self.draw.shuffle(rng);
                     }
                     let card = some_or!(self.draw.pop(), break);
                     let mut ev = action.carry(event::Drew);
@@ -144,6 +174,27 @@ impl Creature {
                 self.discard.push(self.hand.remove(ix));
                 return simple(event::Discarded);
             }
+            (Path::Part { cid, pid }, action::Sever) => {
+                let (mut out, was_open, vital) = self.sever(*cid, *pid, action);
+                if out.is_empty() { return Err(Error::NoSuchPart); }
+                if vital {
+                    out.push(creature_event(*cid, action, event::Died));
+                }
+                out.extend(self.clamp_points(*cid, action));
+                if was_open && self.open_parts().next().is_none() {
+                    self.open_random(rng);
+                }
+                if vital {
+                    self.dead = true;
+                }
+                return Ok(out);
+            }
+            (Path::Part { cid, pid }, action::Attach { part, joint }) => {
+                let new_pid = self.attach(*pid, *joint, part.clone())?;
+                let mut ev = action.carry(event::Attached { parent: *pid });
+                ev.target = Path::Part { cid: *cid, pid: new_pid };
+                return Ok(vec![ev]);
+            }
             _ => (),
         }
         if let Some((cid, pid)) = action.target.part() {
@@ -153,39 +204,28 @@ impl Creature {
             let new_tags = part.tags();
             let mut self_died = false;
             if new_tags.difference(&old_tags).any(|t| *t == PartTag::Broken) {
-                let self_ev = |ev| {
-                    let mut out = action.carry(ev);
-                    out.target = Path::Creature { cid };
-                    out
-                };
-                if part.tags().contains(&PartTag::Vital) && !self_died {
+                if new_tags.contains(&PartTag::Vital) && !self_died {
                     self_died = true;
-                    out.push(self_ev(event::Died));
-                }
-                if self.cur_ap > self.max_ap() {
-                    out.push(self_ev(event::ChangeAP {
-                        delta: self.max_ap() - self.cur_ap,
-                    }));
-                    self.cur_ap = self.max_ap();
-                }
-                if self.cur_mp > self.max_mp() {
-                    out.push(self_ev(event::ChangeMP {
-                        delta: self.max_mp() - self.cur_mp,
-                    }));
-                    self.cur_mp = self.max_mp();
+                    out.push(creature_event(cid, action, event::Died));
                 }
-                if old_tags.contains(&PartTag::Open) {
-                    let ids: Vec<_> = self.parts.iter()
-                        .filter_map(|(id, part)| {
-                            if part.tags().contains(&PartTag::Broken) { None }
-                            else { Some(*id) }
-                        })
+                // Everything attached to a destroyed torso falls off.
+                if new_tags.contains(&PartTag::Torso) {
+                    let children: Vec<_> = self.parts.get(pid).unwrap().joints.iter()
+                        .filter_map(|j| j.attached)
                         .collect();
-                    if !ids.is_empty() {
-                        let ix = thread_rng().gen_range(0, ids.len());
-                        self.parts.get_mut(ids[ix]).unwrap().base_tags.insert(PartTag::Open);
+                    for child in children {
+                        let (events, _, vital) = self.sever(cid, child, action);
+                        out.extend(events);
+                        if vital && !self_died {
+                            self_died = true;
+                            out.push(creature_event(cid, action, event::Died));
+                        }
                     }
                 }
+                out.extend(self.clamp_points(cid, action));
+                if old_tags.contains(&PartTag::Open) {
+                    self.open_random(rng);
+                }
             }
             if self_died {
                 self.dead = true;
@@ -195,17 +235,154 @@ impl Creature {
         Err(Error::UnhandledAction)
     }
 
-    pub fn reset_cards(&mut self) {
-        self.draw = self.parts.iter()
+    /// Remove `pid` and everything attached below it, along with their cards.
+    pub fn detach(&mut self, pid: Id<Part>) -> Vec<(Id<Part>, Part)> {
+        if let Some((parent, ix)) = self.parent_of(pid) {
+            self.parts.get_mut(parent).unwrap().joints[ix].attached = None;
+        }
+        let ids = self.subtree(pid);
+        let removed: Vec<_> = ids.iter()
+            .filter_map(|&id| self.parts.remove(id).map(|p| (id, p)))
+            .collect();
+        self.draw.retain(|(p, _)| !ids.contains(p));
+        self.hand.retain(|(p, _)| !ids.contains(p));
+        self.discard.retain(|(p, _)| !ids.contains(p));
+        removed
+    }
+
+    /// Attach `part` to the given joint of `parent`; its cards go to the
+    /// discard pile.
+    pub fn attach(&mut self, parent: Id<Part>, joint: usize, mut part: Part) -> Result<Id<Part>> {
+        {
+            let parent_part = self.parts.get(parent).ok_or(Error::NoSuchPart)?;
+            let joint = parent_part.joints.get(joint).ok_or(Error::InvalidTarget)?;
+            if joint.attached.is_some() { return Err(Error::Obstructed); }
+            if !joint.accepts(&part) { return Err(Error::InvalidTarget); }
+        }
+        // Anything that was attached below it stayed behind.
+        for j in &mut part.joints {
+            j.attached = None;
+        }
+        let pid = self.parts.add(part);
+        self.parts.get_mut(parent).unwrap().joints[joint].attached = Some(pid);
+        let cards: Vec<_> = self.parts.get(pid).unwrap().cards.keys()
+            .map(|&card| (pid, card))
+            .collect();
+        self.discard.extend(cards);
+        Ok(pid)
+    }
+
+    /// Attach parts removed by `detach` (from any creature) under `parent`,
+    /// keeping their shape.  Returns the new ids, in the same order.
+    pub fn attach_tree(&mut self, parent: Id<Part>, joint: usize, tree: Vec<(Id<Part>, Part)>) -> Result<Vec<Id<Part>>> {
+        let mut placement = HashMap::new();
+        for (old_id, part) in &tree {
+            for (ix, j) in part.joints.iter().enumerate() {
+                if let Some(child) = j.attached {
+                    placement.insert(child, (*old_id, ix));
+                }
+            }
+        }
+        let mut new_ids: HashMap<Id<Part>, Id<Part>> = HashMap::new();
+        let mut out = vec![];
+        for (old_id, part) in tree {
+            let (to, ix) = match placement.get(&old_id) {
+                None => (parent, joint),
+                Some((old_parent, ix)) => (*new_ids.get(old_parent).ok_or(Error::NoSuchPart)?, *ix),
+            };
+            let pid = self.attach(to, ix, part)?;
+            new_ids.insert(old_id, pid);
+            out.push(pid);
+        }
+        Ok(out)
+    }
+
+    /// A copy ready for the next fight: AP and MP refilled, and statuses and
+    /// tag mods cleared.  Damage and broken parts carry over.
+    pub fn rested(&self) -> Creature {
+        let mut out = self.clone();
+        out.entity = Entity::new();
+        for part in out.parts.values_mut() {
+            part.entity = Entity::new();
+            part.tag_mods = ModStack::new();
+        }
+        out.cur_ap = out.max_ap();
+        out.cur_mp = out.max_mp();
+        out
+    }
+
+    pub fn reset_cards<R: Rng>(&mut self, rng: &mut R) {
+        self.draw = self.all_cards();
+        self.draw.shuffle(rng);
+        self.hand = vec![];
+        self.discard = vec![];
+    }
+
+    // Private
+
+    fn all_cards(&self) -> Vec<CardId> {
+        self.parts.iter()
             .flat_map(|(&id, part)|
                 part.cards.keys()
                     .map(move |&cid| (id, cid))
-            ).collect();
-        self.draw.shuffle(&mut thread_rng());
-        self.hand = vec![];
-        self.discard = vec![];
+            ).collect()
+    }
+
+    // Returns the Severed events, whether any removed part was open, and
+    // whether any was vital.
+    fn sever(&mut self, cid: Id<Creature>, pid: Id<Part>, action: &Action) -> (Vec<Event>, bool, bool) {
+        let removed = self.detach(pid);
+        let was_open = removed.iter().any(|(_, p)| p.tags().contains(&PartTag::Open));
+        let vital = removed.iter().any(|(_, p)| {
+            let tags = p.tags();
+            tags.contains(&PartTag::Vital) && !tags.contains(&PartTag::Broken)
+        });
+        let events = removed.iter()
+            .map(|&(pid, _)| {
+                let mut ev = action.carry(event::Severed);
+                ev.target = Path::Part { cid, pid };
+                ev
+            })
+            .collect();
+        (events, was_open, vital)
+    }
+
+    fn clamp_points(&mut self, cid: Id<Creature>, action: &Action) -> Vec<Event> {
+        let mut out = vec![];
+        if self.cur_ap > self.max_ap() {
+            out.push(creature_event(cid, action, event::ChangeAP {
+                delta: self.max_ap() - self.cur_ap,
+            }));
+            self.cur_ap = self.max_ap();
+        }
+        if self.cur_mp > self.max_mp() {
+            out.push(creature_event(cid, action, event::ChangeMP {
+                delta: self.max_mp() - self.cur_mp,
+            }));
+            self.cur_mp = self.max_mp();
+        }
+        out
+    }
+
+    fn open_random<R: Rng>(&mut self, rng: &mut R) {
+        let ids: Vec<_> = self.parts.iter()
+            .filter_map(|(id, part)| {
+                if part.tags().contains(&PartTag::Broken) { None }
+                else { Some(*id) }
+            })
+            .collect();
+        if !ids.is_empty() {
+            let ix = rng.gen_range(0, ids.len());
+            self.parts.get_mut(ids[ix]).unwrap().base_tags.insert(PartTag::Open);
+        }
     }
 
     // TODO: more fine-grained access
     pub fn npc_mut(&mut self) -> Option<&mut NPC> { self.npc.as_mut() }
-}
\ No newline at end of file
+}
+
+fn creature_event(cid: Id<Creature>, action: &Action, data: EventData) -> Event {
+    let mut out = action.carry(data);
+    out.target = Path::Creature { cid };
+    out
+}
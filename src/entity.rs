@@ -1,5 +1,7 @@
 use std::collections::HashSet;
 
+use serde::{Deserialize, Serialize};
+
 use crate::{
     action::{Action, Path},
     id_map::IdMap,
@@ -7,7 +9,7 @@ use crate::{
     some_or,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Entity {
     pub status: IdMap<Box<dyn Status>>,
 }
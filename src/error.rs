@@ -8,6 +8,8 @@ pub enum Error {
     OutOfBounds,
     #[error("Out of range")]
     OutOfRange,
+    #[error("Invalid target")]
+    InvalidTarget,
     #[error("No such creature")]
     NoSuchCreature,
     #[error("No such part")]
@@ -20,10 +22,22 @@ pub enum Error {
     DeadCreature,
     #[error("Broken part")]
     BrokenPart,
+    #[error("Game over")]
+    GameOver,
     #[error("Not enough {0}")]
     NotEnough(String),
     #[error("Invalid action")]
     InvalidAction,
+    #[error("Unregistered id {0}")]
+    Unregistered(String),
+    #[error("Invalid save: {0}")]
+    InvalidSave(String),
+    #[error("Line {line}, column {column}: {message}")]
+    Parse { line: usize, column: usize, message: String },
+    #[error("No usable map could be generated")]
+    MapGen,
+    #[error("Replay mismatch: expected checksum {expected}, got {actual}")]
+    ReplayMismatch { expected: u64, actual: u64 },
     #[error("Unhandled action")]
     UnhandledAction,  // TODO: include the action
 }
@@ -0,0 +1,94 @@
+use std::collections::HashSet;
+
+use crate::{
+    command::Command,
+    creature::Creature,
+    id_map::Id,
+    world::World,
+};
+
+/// Decides whether the transition from `before` to `after` via `command` can
+/// be undone.  Committing a command that can't be undone clears the history.
+pub type UndoRule = fn(command: &Command, before: &World, after: &World) -> bool;
+
+/// Moves and card plays are undoable unless they drew on the world's
+/// randomness or showed the player something new (either of which they could
+/// exploit); ending the turn never is.
+pub fn default_undo_rule(command: &Command, before: &World, after: &World) -> bool {
+    match command {
+        Command::EndTurn => false,
+        Command::MovePlayer { .. } | Command::PlayCard { .. } | Command::Harvest { .. } =>
+            before.rng_draws() == after.rng_draws()
+                && before.known() == after.known()
+                && seen_creatures(before) == seen_creatures(after),
+    }
+}
+
+fn seen_creatures(world: &World) -> HashSet<Id<Creature>> {
+    let player_id = world.player_id();
+    world.creatures().keys().cloned()
+        .filter(|&id| id != player_id && world.sees(player_id, id))
+        .collect()
+}
+
+#[derive(Debug, Clone)]
+pub struct History {
+    base: World,
+    done: Vec<Entry>,
+    undone: Vec<Entry>,
+    rule: UndoRule,
+}
+
+#[derive(Debug, Clone)]
+struct Entry {
+    command: Command,
+    world: World,
+}
+
+impl History {
+    pub fn new(world: World) -> Self {
+        History::with_rule(world, default_undo_rule)
+    }
+
+    pub fn with_rule(world: World, rule: UndoRule) -> Self {
+        History {
+            base: world,
+            done: vec![],
+            undone: vec![],
+            rule,
+        }
+    }
+
+    // Accessors
+
+    pub fn current(&self) -> &World {
+        self.done.last().map_or(&self.base, |e| &e.world)
+    }
+
+    pub fn can_undo(&self) -> bool { !self.done.is_empty() }
+    pub fn can_redo(&self) -> bool { !self.undone.is_empty() }
+
+    // Mutators
+
+    pub fn commit(&mut self, command: Command, world: World) {
+        self.undone.clear();
+        if (self.rule)(&command, self.current(), &world) {
+            self.done.push(Entry { command, world });
+        } else {
+            self.done.clear();
+            self.base = world;
+        }
+    }
+
+    pub fn undo(&mut self) -> Option<&World> {
+        let entry = self.done.pop()?;
+        self.undone.push(entry);
+        Some(self.current())
+    }
+
+    pub fn redo(&mut self) -> Option<&World> {
+        let entry = self.undone.pop()?;
+        self.done.push(entry);
+        Some(self.current())
+    }
+}
@@ -1,8 +1,8 @@
 use std::{
     cmp::Ordering,
     collections::{
-        HashMap,
-        hash_map::{Iter, Keys, Values},
+        BTreeMap,
+        btree_map::{Iter, Keys, Values},
     },
     fmt,
     hash::{Hash, Hasher},
@@ -12,11 +12,11 @@ use std::{
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone)]
-#[derive(Serialize)]
-#[serde(transparent)]
+#[derive(Serialize, Deserialize)]
 pub struct IdMap<T> {
-    map: HashMap<Id<T>, T>,
-    #[serde(skip)]
+    // Ordered so that iteration (and anything random seeded off of it) is
+    // deterministic.
+    map: BTreeMap<Id<T>, T>,
     next_id: Id<T>,
 }
 
@@ -24,7 +24,7 @@ pub struct IdMap<T> {
 impl<T> IdMap<T> {
     pub fn new() -> IdMap<T> {
         IdMap {
-            map: HashMap::new(),
+            map: BTreeMap::new(),
             next_id: Id::new(0),
         }
     }
@@ -33,6 +33,8 @@ impl<T> IdMap<T> {
     pub fn iter(&self) -> Iter<Id<T>, T> { self.map.iter() }
     pub fn keys(&self) -> Keys<Id<T>, T> { self.map.keys() }
     pub fn values(&self) -> Values<Id<T>, T> { self.map.values() }
+    pub fn len(&self) -> usize { self.map.len() }
+    pub fn is_empty(&self) -> bool { self.map.is_empty() }
 
     pub fn add(&mut self, value: T) -> Id<T> {
         let id = self.next_id.inc();
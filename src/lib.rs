@@ -1,29 +1,45 @@
-mod action;
-mod card;
-mod creature;
-mod error;
-mod entity;
-mod id_map;
-mod library;
-mod map;
-mod mod_stack;
-mod npc;
-mod part;
+pub mod action;
+pub mod card;
+pub mod command;
+pub mod creature;
+pub mod error;
+pub mod entity;
+pub mod history;
+pub mod id_map;
+pub mod library;
+pub mod map;
+pub mod map_file;
+pub mod map_gen;
+pub mod mod_stack;
+pub mod npc;
+pub mod part;
+pub mod registry;
+pub mod replay;
+pub mod reward;
+pub mod rng;
+pub mod run;
+pub mod save;
 mod serde_empty;
-mod status;
-mod util;
+mod serde_pairs;
+pub mod status;
+pub mod util;
+#[cfg(feature = "wasm")]
 mod wasm;
-mod world;
-mod world_ext;
+pub mod world;
+pub mod world_ext;
 
+#[cfg(feature = "wasm")]
 use log::{Level, info};
+#[cfg(feature = "wasm")]
 use wasm_bindgen::prelude::*;
 
+#[cfg(feature = "wasm")]
 #[wasm_bindgen(raw_module = "../ts/for_rust")]
 extern "C" {
     fn js_greet(name: &str);
 }
 
+#[cfg(feature = "wasm")]
 #[wasm_bindgen(start)]
 pub fn wasm_start() {
     std::panic::set_hook(Box::new(console_error_panic_hook::hook));
@@ -31,4 +47,4 @@ pub fn wasm_start() {
 
     info!("Parts Unknown WASM initialized.");
     js_greet("User");
-}
\ No newline at end of file
+}
@@ -5,25 +5,52 @@ use std::{
 
 use enum_iterator::IntoEnumIterator;
 use hex::Hex;
-use rand::prelude::*;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
 use serde_json::{self, json};
 
 use crate::{
     action::{Action, Event, Path, Tag, action, event, to_creature},
-    card::{self, Card, TargetSpec},
+    card::{self, Card, HexShape, TargetSpec},
     creature::{Creature},
     id_map::Id,
-    mod_stack::Mod,
     part::{Part, PartTag, TagMod},
+    registry::{Registry, Saved},
     status::{AlterOrder, Status, StatusDone},
     world::{Scope, World},
     world_ext::WorldExt,
 };
 
+pub fn register(reg: &mut Registry) {
+    reg.add_card(throw_debris);
+    reg.add_card(punch);
+    reg.add_card(flurry);
+    reg.add_card(leap);
+    reg.add_card(shockwave);
+    reg.add_card(spit);
+    reg.add_card(sweep);
+    reg.add_card(firebomb);
+    reg.add_card(caltrops);
+    reg.add_card(guard);
+    reg.add_card(stagger);
+    reg.add_card(shove);
+    reg.add_card(grab);
+    reg.add_card(smash);
+    reg.add_card(heal);
+    reg.add_card(rage);
+    reg.add_card(debug_debuff);
+    reg.add_status::<Expire>();
+    reg.add_status::<Rage>();
+    reg.add_status::<Burning>();
+    reg.add_status::<Caltrops>();
+    reg.add_status::<DebugDebuff>();
+}
+
 struct HitPart {
     damage: i32,
     tags: Vec<Vec<PartTag>>,
     melee: bool,
+    count: usize,
 }
 
 impl HitPart {
@@ -36,9 +63,10 @@ impl HitPart {
             world.map().los_of(cid).unwrap()
         };
         Box::new(HitPartBehavior {
-            damage: self.damage,
+            damage: powered(world, source, self.damage),
             tags: self.tags,
-            range
+            range,
+            count: self.count,
         })
     }
 }
@@ -48,15 +76,17 @@ struct HitPartBehavior {
     damage: i32,
     tags: Vec<Vec<PartTag>>,
     range: HashSet<Hex>,
+    count: usize,
 }
 
 impl card::Behavior for HitPartBehavior {
-    fn range(&self, _source: &Path, _world: &World) -> Vec<Hex> { self.range.iter().cloned().collect() }
-    fn target_spec(&self) -> TargetSpec { TargetSpec::Part { on_player: false, tags: self.tags.clone() } }
-    fn target_check(&self, _world: &World, source: &Path, target: &Path) -> bool {
-        target.creature().unwrap() != source.creature().unwrap()
+    fn target_count(&self) -> usize { self.count }
+    fn range(&self, _source: &Path, _world: &World, _prior: &[Path]) -> Vec<Hex> { self.range.iter().cloned().collect() }
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec { TargetSpec::Part { on_player: false, tags: self.tags.clone() } }
+    fn target_check(&self, _world: &World, source: &Path, prior: &[Path], target: &Path) -> bool {
+        target.creature().unwrap() != source.creature().unwrap() && !prior.contains(target)
     }
-    fn apply(&self, world: &mut World, source: Path, target: Path) -> Vec<Event> {
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event> {
         let source_cid = source.creature().unwrap();
         let source_creature = world.creatures().get(source_cid).unwrap();
         let source_mp = source_creature.cur_mp;
@@ -67,15 +97,25 @@ impl card::Behavior for HitPartBehavior {
             tags: HashSet::from_iter(vec![Tag::NoRender]),
             data: action::SpendMP { mp: source_mp },
         }));
-        out.extend(world.execute(&Action {
-            source, target,
-            tags: HashSet::from_iter(vec![Tag::Attack]),  // TODO: melee tag
-            data: action::Hit { damage: self.damage },
-        }));
+        for target in targets {
+            out.extend(world.execute(&Action {
+                source: source.clone(),
+                target: target.clone(),
+                tags: HashSet::from_iter(vec![Tag::Attack]),  // TODO: melee tag
+                data: action::Hit { damage: self.damage },
+            }));
+        }
         out
     }
 }
 
+// `base` scaled by the power of the part playing the card.
+fn powered(world: &World, source: &Path, base: i32) -> i32 {
+    source.part()
+        .and_then(|(cid, pid)| world.creatures().get(cid)?.parts.get(pid))
+        .map_or(base, |part| part.scale(base))
+}
+
 fn no_ui(_world: &World, _source: &Path, _target: &Path) -> serde_json::Value {
     json!({
         "test": "value",
@@ -107,7 +147,7 @@ fn attack_ui(world: &World, source: &Path, target: &Path, base: i32) -> serde_js
     } else {
         target
     };
-    let (damage, action) = world.scale_damage(source, target, base, Scope::into_enum_iter());
+    let (damage, action) = world.scale_damage(source, target, powered(world, source, base), Scope::into_enum_iter());
     let mut out = scaled("damage", base, damage);
     out["tags"] = action.tags.into_iter()
         .map(|tag| format!("{:?}", tag))
@@ -118,12 +158,14 @@ fn attack_ui(world: &World, source: &Path, target: &Path, base: i32) -> serde_js
 pub fn throw_debris() -> Card {
     static DAMAGE: i32 = 5;
     Card {
+        key: "throw_debris",
         name: "Throw Debris".into(),
         ap_cost: 1,
         start_play: |world, source| HitPart {
             damage: DAMAGE,
             tags: vec![vec![PartTag::Open]],
             melee: false,
+            count: 1,
         }.behavior(world, source),
         ui: |world, source, target| attack_ui(world, source, target, DAMAGE),
     }
@@ -132,25 +174,209 @@ pub fn throw_debris() -> Card {
 pub fn punch() -> Card {
     static DAMAGE: i32 = 10;
     Card {
+        key: "punch",
         name: "Punch".into(),
         ap_cost: 1,
         start_play: |world, source| HitPart {
             damage: DAMAGE,
             tags: vec![vec![PartTag::Open]],
             melee: true,
+            count: 1,
         }.behavior(world, source),
         ui: |world, source, target| attack_ui(world, source, target, DAMAGE),
     }
 }
 
-#[derive(Clone)]
-struct Expire<When> {
+pub fn flurry() -> Card {
+    static DAMAGE: i32 = 6;
+    Card {
+        key: "flurry",
+        name: "Flurry".into(),
+        ap_cost: 2,
+        start_play: |world, source| HitPart {
+            damage: DAMAGE,
+            tags: vec![vec![PartTag::Open]],
+            melee: true,
+            count: 2,
+        }.behavior(world, source),
+        ui: |world, source, target| attack_ui(world, source, target, DAMAGE),
+    }
+}
+
+pub fn leap() -> Card {
+    static DISTANCE: i32 = 2;
+    Card {
+        key: "leap",
+        name: "Leap".into(),
+        ap_cost: 1,
+        start_play: |world, source| {
+            let cid = source.creature().unwrap();
+            let range = world.map().range_from(cid, DISTANCE, true);
+            Box::new(Leap { range })
+        },
+        ui: no_ui,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Leap {
+    range: HashSet<Hex>,
+}
+
+impl card::Behavior for Leap {
+    fn range(&self, _source: &Path, _world: &World, _prior: &[Path]) -> Vec<Hex> { self.range.iter().cloned().collect() }
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec { TargetSpec::Hex { shape: HexShape::Single } }
+    fn target_check(&self, world: &World, source: &Path, _prior: &[Path], target: &Path) -> bool {
+        let cid = source.creature().unwrap();
+        let hex = target.hex(world).unwrap();
+        world.map().footprint_cost(cid, hex).is_some()
+    }
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event> {
+        let cid = source.creature().unwrap();
+        let to = targets[0].hex(world).unwrap();
+        world.execute(&Action {
+            source,
+            target: Path::Creature { cid },
+            tags: HashSet::new(),
+            data: action::Move { to },
+        })
+    }
+}
+
+pub fn shockwave() -> Card {
+    static DAMAGE: i32 = 4;
+    Card {
+        key: "shockwave",
+        name: "Shockwave".into(),
+        ap_cost: 2,
+        start_play: |world, source| {
+            let cid = source.creature().unwrap();
+            let range = world.map().los_of(cid).unwrap();
+            Box::new(Shockwave {
+                damage: powered(world, source, DAMAGE),
+                shape: HexShape::Radius { radius: 1 },
+                range,
+            })
+        },
+        ui: |world, source, _target| attack_ui(world, source, &Path::World, DAMAGE),
+    }
+}
+
+pub fn spit() -> Card {
+    static DAMAGE: i32 = 3;
+    Card {
+        key: "spit",
+        name: "Spit".into(),
+        ap_cost: 1,
+        start_play: |world, source| {
+            let cid = source.creature().unwrap();
+            let &pos = world.map().creatures().get(&cid).unwrap();
+            Box::new(Shockwave {
+                damage: powered(world, source, DAMAGE),
+                shape: HexShape::Line { length: 4 },
+                range: pos.neighbors().collect(),
+            })
+        },
+        ui: |world, source, _target| attack_ui(world, source, &Path::World, DAMAGE),
+    }
+}
+
+pub fn sweep() -> Card {
+    static DAMAGE: i32 = 4;
+    Card {
+        key: "sweep",
+        name: "Sweep".into(),
+        ap_cost: 1,
+        start_play: |world, source| {
+            let cid = source.creature().unwrap();
+            let &pos = world.map().creatures().get(&cid).unwrap();
+            Box::new(Shockwave {
+                damage: powered(world, source, DAMAGE),
+                shape: HexShape::Cone { length: 2 },
+                range: pos.neighbors().collect(),
+            })
+        },
+        ui: |world, source, _target| attack_ui(world, source, &Path::World, DAMAGE),
+    }
+}
+
+// Also backs `spit` and `sweep`, which aim it with an adjacent hex.
+#[derive(Debug, Clone)]
+struct Shockwave {
+    damage: i32,
+    shape: HexShape,
+    range: HashSet<Hex>,
+}
+
+impl card::Behavior for Shockwave {
+    fn range(&self, _source: &Path, _world: &World, _prior: &[Path]) -> Vec<Hex> { self.range.iter().cloned().collect() }
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec { TargetSpec::Hex { shape: self.shape } }
+    fn target_check(&self, _world: &World, _source: &Path, _prior: &[Path], _target: &Path) -> bool { true }
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event> {
+        let source_cid = source.creature().unwrap();
+        let area = TargetSpec::Hex { shape: self.shape }.area(world, &source, &targets[0]);
+        // Every open part of everyone else caught in the blast, and anything
+        // breakable.
+        let mut hits = vec![];
+        let mut caught = HashSet::new();
+        for hex in area {
+            let tile = match world.map().tiles().get(&hex) {
+                Some(t) => t,
+                None => continue,
+            };
+            if tile.object.as_ref().map_or(false, |o| o.hp().is_some()) {
+                hits.push(Path::Hex { hex });
+            }
+            let cid = match tile.creature {
+                Some(cid) if cid != source_cid => cid,
+                _ => continue,
+            };
+            // Big creatures can be in the blast more than once.
+            if !caught.insert(cid) { continue; }
+            let creature = world.creatures().get(cid).unwrap();
+            for (&pid, part) in &creature.parts {
+                if part.tags().contains(&PartTag::Open) {
+                    hits.push(Path::Part { cid, pid });
+                }
+            }
+        }
+        let mut out = vec![];
+        for target in hits {
+            out.extend(world.execute(&Action {
+                source: source.clone(),
+                target,
+                tags: HashSet::from_iter(vec![Tag::Attack]),
+                data: action::Hit { damage: self.damage },
+            }));
+        }
+        out
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct Expire {
     remove: Vec<Action>,
-    when: When
+    when: ExpireOn,
+}
+
+#[derive(Debug, Copy, Clone, Serialize, Deserialize)]
+enum ExpireOn {
+    PlayerTurnEnd,
+    NpcTurnEnd,
+}
+
+impl ExpireOn {
+    fn matches(&self, event: &Event) -> bool {
+        match (self, &event.data) {
+            (ExpireOn::PlayerTurnEnd, event::PlayerTurnEnd) => true,
+            (ExpireOn::NpcTurnEnd, event::NpcTurnEnd) => true,
+            _ => false,
+        }
+    }
 }
 
-impl<When: Fn(&Event) -> bool + Clone + 'static> Expire<When> {
-    fn tag_mod(world: &mut World, target: &Path, m: TagMod, when: When) -> Vec<Event> {
+impl Expire {
+    fn tag_mod(world: &mut World, target: &Path, m: TagMod, when: ExpireOn) -> Vec<Event> {
         let mut out = world.execute(&Action {
             source: Path::World, target: target.clone(),
             tags: HashSet::new(),
@@ -179,23 +405,168 @@ impl<When: Fn(&Event) -> bool + Clone + 'static> Expire<When> {
     }
 }
 
-impl<When> std::fmt::Debug for Expire<When> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_struct("Expire")
-            .finish()
-    }
-}
-
-impl<When: Fn(&Event) -> bool + Clone + 'static> Status for Expire<When> {
+impl Status for Expire {
     fn name(&self) -> &'static str { "Expire" }
     fn trigger(&mut self, _on: &Path, event: &Event) -> (Vec<Action>, StatusDone) {
-        if !(self.when)(event) { return (vec![], StatusDone::Continue); }
+        if !self.when.matches(event) { return (vec![], StatusDone::Continue); }
         (self.remove.clone(), StatusDone::Expire)
     }
 }
 
+impl Saved for Expire { const KEY: &'static str = "expire"; }
+
+pub fn firebomb() -> Card {
+    static DAMAGE: i32 = 2;
+    static TURNS: i32 = 3;
+    Card {
+        key: "firebomb",
+        name: "Firebomb".into(),
+        ap_cost: 2,
+        start_play: |world, source| {
+            let cid = source.creature().unwrap();
+            let range = world.map().los_of(cid).unwrap();
+            Box::new(Firebomb {
+                damage: powered(world, source, DAMAGE),
+                turns: TURNS,
+                shape: HexShape::Radius { radius: 1 },
+                range,
+            })
+        },
+        ui: |world, source, _target| attack_ui(world, source, &Path::World, DAMAGE),
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Firebomb {
+    damage: i32,
+    turns: i32,
+    shape: HexShape,
+    range: HashSet<Hex>,
+}
+
+impl card::Behavior for Firebomb {
+    fn range(&self, _source: &Path, _world: &World, _prior: &[Path]) -> Vec<Hex> { self.range.iter().cloned().collect() }
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec { TargetSpec::Hex { shape: self.shape } }
+    fn target_check(&self, _world: &World, _source: &Path, _prior: &[Path], _target: &Path) -> bool { true }
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event> {
+        let area = TargetSpec::Hex { shape: self.shape }.area(world, &source, &targets[0]);
+        let mut out = vec![];
+        for hex in area {
+            out.extend(world.execute(&Action {
+                source: source.clone(),
+                target: Path::Hex { hex },
+                tags: HashSet::new(),
+                data: action::AddStatus {
+                    status: Box::new(Burning { damage: self.damage, turns: self.turns }),
+                },
+            }));
+        }
+        out
+    }
+}
+
+/// Burning ground: hits anyone who steps in, and whoever's standing there
+/// at the end of each turn, until it burns out.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Burning {
+    pub damage: i32,
+    pub turns: i32,
+}
+
+impl Status for Burning {
+    fn name(&self) -> &'static str { "Burning" }
+    fn trigger(&mut self, on: &Path, event: &Event) -> (Vec<Action>, StatusDone) {
+        let hit = Action {
+            source: on.clone(),
+            target: on.clone(),
+            tags: HashSet::new(),
+            data: action::Hit { damage: self.damage },
+        };
+        match event.data {
+            // Hex statuses only see moves that end on them.
+            event::Moved { .. } => (vec![hit], StatusDone::Continue),
+            event::PlayerTurnEnd => (vec![hit], StatusDone::Continue),
+            event::NpcTurnEnd => {
+                self.turns -= 1;
+                let done = if self.turns <= 0 { StatusDone::Expire } else { StatusDone::Continue };
+                (vec![hit], done)
+            }
+            _ => (vec![], StatusDone::Continue),
+        }
+    }
+}
+
+impl Saved for Burning { const KEY: &'static str = "burning"; }
+
+pub fn caltrops() -> Card {
+    static DAMAGE: i32 = 4;
+    static RANGE: i32 = 2;
+    Card {
+        key: "caltrops",
+        name: "Caltrops".into(),
+        ap_cost: 1,
+        start_play: |world, source| {
+            let cid = source.creature().unwrap();
+            let &at = world.map().creatures().get(&cid).unwrap();
+            let range = world.map().los_of(cid).unwrap().into_iter()
+                .filter(|h| h.distance_to(at) <= RANGE)
+                .collect();
+            Box::new(Scatter { damage: powered(world, source, DAMAGE), range })
+        },
+        ui: |world, source, _| { scaled("damage", DAMAGE, Some(powered(world, source, DAMAGE))) },
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Scatter {
+    damage: i32,
+    range: HashSet<Hex>,
+}
+
+impl card::Behavior for Scatter {
+    fn range(&self, _source: &Path, _world: &World, _prior: &[Path]) -> Vec<Hex> { self.range.iter().cloned().collect() }
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec { TargetSpec::Hex { shape: HexShape::Single } }
+    fn target_check(&self, world: &World, _source: &Path, _prior: &[Path], target: &Path) -> bool {
+        let hex = target.hex(world).unwrap();
+        world.map().tiles().get(&hex).map_or(false, |t| t.creature.is_none() && t.is_open())
+    }
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event> {
+        let hex = targets[0].hex(world).unwrap();
+        world.execute(&Action {
+            source,
+            target: Path::Hex { hex },
+            tags: HashSet::new(),
+            data: action::AddStatus { status: Box::new(Caltrops { damage: self.damage }) },
+        })
+    }
+}
+
+/// Caltrops: the first one to step in gets hurt, and that's the end of them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Caltrops {
+    pub damage: i32,
+}
+
+impl Status for Caltrops {
+    fn name(&self) -> &'static str { "Caltrops" }
+    fn trigger(&mut self, on: &Path, event: &Event) -> (Vec<Action>, StatusDone) {
+        match event.data {
+            event::Moved { .. } => (vec![Action {
+                source: on.clone(),
+                target: on.clone(),
+                tags: HashSet::new(),
+                data: action::Hit { damage: self.damage },
+            }], StatusDone::Expire),
+            _ => (vec![], StatusDone::Continue),
+        }
+    }
+}
+
+impl Saved for Caltrops { const KEY: &'static str = "caltrops"; }
+
 pub fn guard() -> Card {
     Card {
+        key: "guard",
         name: "Block".into(),
         ap_cost: 1,
         start_play: |_, _| Box::new(Guard),
@@ -207,22 +578,23 @@ pub fn guard() -> Card {
 struct Guard;
 
 impl card::Behavior for Guard {
-    fn range(&self, _source: &Path, _world: &World) -> Vec<Hex> { vec![] }
-    fn target_spec(&self) -> TargetSpec {
+    fn range(&self, _source: &Path, _world: &World, _prior: &[Path]) -> Vec<Hex> { vec![] }
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec {
         TargetSpec::Part { on_player: true, tags: vec![vec![PartTag::Open]] }
     }
-    fn target_check(&self, _world: &World, source: &Path, target: &Path) -> bool {
+    fn target_check(&self, _world: &World, source: &Path, _prior: &[Path], target: &Path) -> bool {
         source.part() != target.part()
     }
-    fn apply(&self, world: &mut World, source: Path, target: Path) -> Vec<Event> {
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event> {
+        let target = &targets[0];
         let mut out = vec![];
         out.extend(Expire::tag_mod(world, &source,
-            Mod(|tags| { tags.insert(PartTag::Open); }),
-            |ev| matches!(ev, Event { data: event::NpcTurnEnd, .. })
+            TagMod::Add(PartTag::Open),
+            ExpireOn::NpcTurnEnd,
         ));
-        out.extend(Expire::tag_mod(world, &target,
-            Mod(|tags| { tags.remove(&PartTag::Open); }),
-            |ev| matches!(ev, Event { data: event::NpcTurnEnd, .. })
+        out.extend(Expire::tag_mod(world, target,
+            TagMod::Remove(PartTag::Open),
+            ExpireOn::NpcTurnEnd,
         ));
         out
     }
@@ -230,6 +602,7 @@ impl card::Behavior for Guard {
 
 pub fn stagger() -> Card {
     Card {
+        key: "stagger",
         name: "Stagger".into(),
         ap_cost: 1,
         start_play: |_, _| Box::new(Stagger),
@@ -241,32 +614,32 @@ pub fn stagger() -> Card {
 struct Stagger;
 
 impl card::Behavior for Stagger {
-    fn range(&self, _source: &Path, world: &World) -> Vec<Hex> {
+    fn range(&self, _source: &Path, world: &World, _prior: &[Path]) -> Vec<Hex> {
         let pos = world.map().creatures().get(&world.player_id()).unwrap();
         pos.neighbors().collect()
     }
-    fn target_spec(&self) -> TargetSpec {
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec {
         TargetSpec::Creature
     }
-    fn target_check(&self, world: &World, _source: &Path, target: &Path) -> bool {
+    fn target_check(&self, world: &World, _source: &Path, _prior: &[Path], target: &Path) -> bool {
         !Stagger::target_parts(world, target.creature().unwrap()).is_empty()
     }
-    fn preview(&self, _world: &World, _source: Path, target: Path) -> Vec<Event> {
-        let creature_id = target.creature().unwrap();
+    fn preview(&self, _world: &World, _source: Path, targets: &[Path]) -> Vec<Event> {
+        let creature_id = targets[0].creature().unwrap();
         vec![to_creature(creature_id, event::FloatText { text: "Stagger!".into() })]
     }
-    fn apply(&self, world: &mut World, _source: Path, target: Path) -> Vec<Event> {
-        let cid = target.creature().unwrap();
+    fn apply(&self, world: &mut World, _source: Path, targets: &[Path]) -> Vec<Event> {
+        let cid = targets[0].creature().unwrap();
         let part_ids = Stagger::target_parts(world, cid);
         if part_ids.is_empty() { return vec![]; }
 
-        let ix = thread_rng().gen_range(0, part_ids.len());
+        let ix = world.rng().gen_range(0, part_ids.len());
         let (name, pid) = &part_ids[ix];
         let mut out = vec![];
         out.push(to_creature(cid, event::FloatText { text: format!("Exposed: {}", name) }));
         out.extend(Expire::tag_mod(world, &Path::Part { cid, pid: *pid },
-            Mod(|tags| { tags.insert(PartTag::Open); }),
-            |ev| matches!(ev, Event { data: event::PlayerTurnEnd, .. })));
+            TagMod::Add(PartTag::Open),
+            ExpireOn::PlayerTurnEnd));
         out
     }
 }
@@ -282,13 +655,110 @@ impl Stagger {
     }
 }
 
+pub fn shove() -> Card {
+    static DISTANCE: i32 = 2;
+    Card {
+        key: "shove",
+        name: "Shove".into(),
+        ap_cost: 1,
+        start_play: |world, source| {
+            let cid = source.creature().unwrap();
+            let &pos = world.map().creatures().get(&cid).unwrap();
+            Box::new(Shove { pull: false, distance: DISTANCE, range: pos.neighbors().collect() })
+        },
+        ui: no_ui,
+    }
+}
+
+pub fn grab() -> Card {
+    static DISTANCE: i32 = 2;
+    static RANGE: i32 = 3;
+    Card {
+        key: "grab",
+        name: "Grab".into(),
+        ap_cost: 1,
+        start_play: |world, source| {
+            let cid = source.creature().unwrap();
+            let &pos = world.map().creatures().get(&cid).unwrap();
+            let range = world.map().los_of(cid).unwrap().into_iter()
+                .filter(|h| h.distance_to(pos) <= RANGE)
+                .collect();
+            Box::new(Shove { pull: true, distance: DISTANCE, range })
+        },
+        ui: no_ui,
+    }
+}
+
+// Moves the target creature away from (or toward, for `pull`) the player.
+#[derive(Debug, Clone)]
+struct Shove {
+    pull: bool,
+    distance: i32,
+    range: HashSet<Hex>,
+}
+
+impl card::Behavior for Shove {
+    fn range(&self, _source: &Path, _world: &World, _prior: &[Path]) -> Vec<Hex> { self.range.iter().cloned().collect() }
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec { TargetSpec::Creature }
+    fn target_check(&self, _world: &World, source: &Path, _prior: &[Path], target: &Path) -> bool {
+        target.creature() != source.creature()
+    }
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event> {
+        let cid = targets[0].creature().unwrap();
+        if self.pull {
+            world.pull(&source, cid, self.distance)
+        } else {
+            world.push(&source, cid, self.distance)
+        }
+    }
+}
+
+pub fn smash() -> Card {
+    static DAMAGE: i32 = 10;
+    Card {
+        key: "smash",
+        name: "Smash".into(),
+        ap_cost: 1,
+        start_play: |world, source| {
+            let cid = source.creature().unwrap();
+            let &pos = world.map().creatures().get(&cid).unwrap();
+            Box::new(Smash {
+                damage: powered(world, source, DAMAGE),
+                range: pos.neighbors().collect(),
+            })
+        },
+        ui: no_ui,
+    }
+}
+
+#[derive(Debug, Clone)]
+struct Smash {
+    damage: i32,
+    range: HashSet<Hex>,
+}
+
+impl card::Behavior for Smash {
+    fn range(&self, _source: &Path, _world: &World, _prior: &[Path]) -> Vec<Hex> { self.range.iter().cloned().collect() }
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec { TargetSpec::Object }
+    fn target_check(&self, _world: &World, _source: &Path, _prior: &[Path], _target: &Path) -> bool { true }
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event> {
+        world.execute(&Action {
+            source,
+            target: targets[0].clone(),
+            tags: HashSet::from_iter(vec![Tag::Attack]),
+            data: action::Hit { damage: self.damage },
+        })
+    }
+}
+
 pub fn heal() -> Card {
     static AMOUNT: i32 = 5;
     Card {
+        key: "heal",
         name: "Regenerate".into(),
         ap_cost: 1,
-        start_play: |_, _| Box::new(Heal { amount: AMOUNT }),
-        ui: |_, _, _| { scaled("heal", AMOUNT, Some(AMOUNT)) }
+        start_play: |world, source| Box::new(Heal { amount: powered(world, source, AMOUNT) }),
+        ui: |world, source, _| { scaled("heal", AMOUNT, Some(powered(world, source, AMOUNT))) }
     }
 }
 
@@ -298,19 +768,20 @@ struct Heal {
 }
 
 impl card::Behavior for Heal {
-    fn range(&self, _source: &Path, _world: &World) -> Vec<Hex> { vec![] }
-    fn target_spec(&self) -> TargetSpec {
+    fn range(&self, _source: &Path, _world: &World, _prior: &[Path]) -> Vec<Hex> { vec![] }
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec {
         TargetSpec::Part { on_player: true, tags: vec![vec![PartTag::Flesh]] }
     }
-    fn target_check(&self, world: &World, _source: &Path, target: &Path) -> bool {
+    fn target_check(&self, world: &World, _source: &Path, _prior: &[Path], target: &Path) -> bool {
         let (cid, pid) = target.part().unwrap();
         let creature = world.creatures().get(cid).unwrap();
         let part = creature.parts.get(pid).unwrap();
         part.cur_hp < part.max_hp
     }
-    fn apply(&self, world: &mut World, source: Path, target: Path) -> Vec<Event> {
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event> {
         world.execute(&Action {
-            source, target,
+            source,
+            target: targets[0].clone(),
             tags: HashSet::new(),
             data: action::Heal { hp: self.amount },
         })
@@ -320,21 +791,22 @@ impl card::Behavior for Heal {
 pub fn rage() -> Card {
     static AMOUNT: i32 = 7;
     Card {
+        key: "rage",
         name: "Rage".into(),
         ap_cost: 1,
-        start_play: |_, _| Box::new(Rage { amount: AMOUNT }),
-        ui: |_, _, _| { scaled("added", AMOUNT, Some(AMOUNT)) }
+        start_play: |world, source| Box::new(Rage { amount: powered(world, source, AMOUNT) }),
+        ui: |world, source, _| { scaled("added", AMOUNT, Some(powered(world, source, AMOUNT))) }
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct Rage { amount: i32 }
 
 impl card::Behavior for Rage {
-    fn range(&self, _source: &Path, _world: &World) -> Vec<Hex> { vec![] }
-    fn target_spec(&self) -> TargetSpec { TargetSpec::None }
-    fn target_check(&self, _world: &World, _source: &Path, _target: &Path) -> bool { true }
-    fn apply(&self, world: &mut World, source: Path, _target: Path) -> Vec<Event> {
+    fn range(&self, _source: &Path, _world: &World, _prior: &[Path]) -> Vec<Hex> { vec![] }
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec { TargetSpec::None }
+    fn target_check(&self, _world: &World, _source: &Path, _prior: &[Path], _target: &Path) -> bool { true }
+    fn apply(&self, world: &mut World, source: Path, _targets: &[Path]) -> Vec<Event> {
         let cid = source.creature().unwrap();
         world.execute(&Action {
             source,
@@ -367,9 +839,11 @@ impl Status for Rage {
     }
 }
 
-#[allow(unused)]
+impl Saved for Rage { const KEY: &'static str = "rage"; }
+
 pub fn debug_debuff() -> Card {
     Card {
+        key: "debug_debuff",
         name: "Debug Debuff".into(),
         ap_cost: 0,
         start_play: |_, _| Box::new(DebugDebuff),
@@ -377,19 +851,20 @@ pub fn debug_debuff() -> Card {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 struct DebugDebuff;
 
 impl card::Behavior for DebugDebuff {
-    fn range(&self, source: &Path, world: &World) -> Vec<Hex> {
+    fn range(&self, source: &Path, world: &World, _prior: &[Path]) -> Vec<Hex> {
         let cid = source.creature().unwrap();
         world.map().los_of(cid).unwrap().into_iter().collect()
     }
-    fn target_spec(&self) -> TargetSpec { TargetSpec::Creature }
-    fn target_check(&self, _world: &World, _source: &Path, _target: &Path) -> bool { true }
-    fn apply(&self, world: &mut World, source: Path, target: Path) -> Vec<Event> {
+    fn target_spec(&self, _prior: &[Path]) -> TargetSpec { TargetSpec::Creature }
+    fn target_check(&self, _world: &World, _source: &Path, _prior: &[Path], _target: &Path) -> bool { true }
+    fn apply(&self, world: &mut World, source: Path, targets: &[Path]) -> Vec<Event> {
         world.execute(&Action {
-            source, target,
+            source,
+            target: targets[0].clone(),
             tags: HashSet::new(),
             data: action::AddStatus { status: Box::new(DebugDebuff) }
         })
@@ -409,4 +884,6 @@ impl Status for DebugDebuff {
             _ => None,
         }
     }
-}
\ No newline at end of file
+}
+
+impl Saved for DebugDebuff { const KEY: &'static str = "debug_debuff"; }
@@ -0,0 +1,56 @@
+use crate::{
+    creature::Creature,
+    error::{Error, Result},
+    library::{self, npc::{Monopod, Spitter}},
+    map_file::{Layout, Spawn},
+    map_gen::MapGen,
+    run::Encounter,
+};
+
+/// The encounters of a run, in order.
+pub const RUN: &[&str] = &["lone", "pair", "pack"];
+
+pub fn get(key: &str) -> Result<Encounter> {
+    let out = match key {
+        "lone" => Encounter::Generated {
+            enemies: vec![Monopod::creature()],
+            map: MapGen {
+                radius: 5, bites: 2, clusters: 2, cluster_size: 3, chokepoints: 0, patches: 1,
+                min_open: 30,
+            },
+            min_distance: 3,
+        },
+        "pair" => Encounter::Generated {
+            enemies: vec![Monopod::creature(), Monopod::creature()],
+            map: MapGen {
+                radius: 6, bites: 3, clusters: 3, cluster_size: 4, chokepoints: 1, patches: 2,
+                min_open: 28,
+            },
+            min_distance: 4,
+        },
+        "pack" => Encounter::Generated {
+            enemies: vec![Monopod::creature(), Monopod::creature(), Spitter::creature()],
+            map: MapGen {
+                radius: 8, bites: 4, clusters: 4, cluster_size: 5, chokepoints: 2, patches: 3,
+                min_open: 40,
+            },
+            min_distance: 4,
+        },
+        "pillars" => authored(include_str!("maps/pillars.txt"))?,
+        _ => return Err(Error::Unregistered(key.into())),
+    };
+    Ok(out)
+}
+
+/// A fresh creature for a map file spawn.
+pub fn spawn(spawn: Spawn) -> Creature {
+    match spawn {
+        Spawn::Player => library::player::player(),
+        Spawn::Monopod => Monopod::creature(),
+        Spawn::Spitter => Spitter::creature(),
+    }
+}
+
+fn authored(text: &str) -> Result<Encounter> {
+    Ok(Encounter::Authored { layout: Layout::parse(text)? })
+}
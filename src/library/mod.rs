@@ -1,3 +1,13 @@
+use crate::registry::Registry;
+
 pub mod card;
+pub mod encounter;
 pub mod npc;
-pub mod player;
\ No newline at end of file
+pub mod player;
+
+pub fn registry() -> Registry {
+    let mut reg = Registry::new();
+    card::register(&mut reg);
+    npc::register(&mut reg);
+    reg
+}
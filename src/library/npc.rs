@@ -1,12 +1,20 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     creature::{Creature},
     id_map::{Id, IdMap},
     npc::{self, NPC},
-    part::{Part, PartTag},
+    part::{Joint, Part, PartTag},
+    registry::{Registry, Saved},
     world::World,
 };
 
-#[derive(Debug, Clone)]
+pub fn register(reg: &mut Registry) {
+    reg.add_behavior::<Monopod>();
+    reg.add_behavior::<npc::Planner>();
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Monopod {
     kick_time: bool,
     head: Id<Part>,
@@ -30,6 +38,9 @@ impl Monopod {
                 &[PartTag::Limb, PartTag::Flesh, PartTag::Leg, PartTag::Open],
                 20)
         });
+        parts.get_mut(head).unwrap().joints = vec![
+            Joint::new(&[PartTag::Leg], Some(foot)),
+        ];
  
         let mono = Monopod {
             kick_time: true,
@@ -38,6 +49,7 @@ impl Monopod {
         Creature::new_ids("Monopod", parts, Some(NPC {
             intent: mono.kick(),
             behavior: Box::new(mono),
+            last_seen: None,
         }))
     }
 
@@ -49,6 +61,7 @@ impl Monopod {
             kind: npc::IntentKind::Attack {
                 damage: 10,
                 range: npc::Range::Melee,
+                movement: npc::Movement::Approach,
             },
         }
     }
@@ -61,6 +74,7 @@ impl Monopod {
             kind: npc::IntentKind::Attack {
                 damage: 5,
                 range: npc::Range::Melee,
+                movement: npc::Movement::Approach,
             },
         }
     }
@@ -75,4 +89,68 @@ impl npc::Behavior for Monopod {
             vec![self.headbutt(), self.kick()]
         }       
     }
-}
\ No newline at end of file
+}
+
+impl Saved for Monopod { const KEY: &'static str = "monopod"; }
+
+/// Keeps its distance and spits, but bites when cornered; picks between them
+/// with a `Planner`.
+pub struct Spitter;
+
+impl Spitter {
+    pub fn creature() -> Creature {
+        let mut parts = IdMap::new();
+        let head = parts.add(Part {
+            thought: 1,
+            ..Part::new(
+                "Hed",
+                &[PartTag::Head, PartTag::Flesh, PartTag::Vital, PartTag::Open],
+                15)
+        });
+        let sac = parts.add(Part::new(
+            "Sak",
+            &[PartTag::Torso, PartTag::Flesh, PartTag::Open],
+            10));
+        let legs = parts.add(Part {
+            mp: 3,
+            ..Part::new(
+                "Legz",
+                &[PartTag::Limb, PartTag::Flesh, PartTag::Leg, PartTag::Open],
+                15)
+        });
+        parts.get_mut(head).unwrap().joints = vec![
+            Joint::new(&[PartTag::Torso], Some(sac)),
+            Joint::new(&[PartTag::Leg], Some(legs)),
+        ];
+
+        let spit = npc::Intent {
+            name: "Spit".into(),
+            from: Some(sac),
+            cost: 1,
+            kind: npc::IntentKind::Attack {
+                damage: 6,
+                range: npc::Range::Ranged { min: 2, max: 4, needs_los: true },
+                movement: npc::Movement::KeepDistance,
+            },
+        };
+        let bite = npc::Intent {
+            name: "Bite".into(),
+            from: Some(head),
+            cost: 1,
+            kind: npc::IntentKind::Attack {
+                damage: 8,
+                range: npc::Range::Melee,
+                movement: npc::Movement::Approach,
+            },
+        };
+        let planner = npc::Planner::new(
+            vec![spit.clone(), bite],
+            npc::Utility { exposed: 5, ..npc::Utility::default() },
+        );
+        Creature::new_ids("Spitter", parts, Some(NPC {
+            intent: spit,
+            behavior: Box::new(planner),
+            last_seen: None,
+        }))
+    }
+}
@@ -1,52 +1,134 @@
-use std::iter::FromIterator;
-
 use crate::{
+    card::Card,
     creature::{Creature},
     id_map::IdMap,
     library,
-    part::{Part, PartTag},
+    part::{Joint, Part, PartTag},
 };
 
 pub fn player() -> Creature {
-    let head = Part {
-        cards: IdMap::from_iter(vec![
+    let arm = || vec![
+        library::card::throw_debris(),
+        library::card::punch(),
+        library::card::guard(),
+    ];
+    body(Loadout {
+        head: vec![
             library::card::rage(),
             //library::card::debug_debuff(),
-        ]),
+        ],
+        torso: vec![library::card::heal()],
+        arm_l: arm(),
+        arm_r: arm(),
+        leg_l: vec![library::card::stagger()],
+        leg_r: vec![library::card::stagger()],
+    })
+}
+
+/// The player's body carrying every card, for trying them out without having
+/// to win them first; see the terminal client's `debug` flag.
+pub fn debug_player() -> Creature {
+    body(Loadout {
+        head: vec![
+            library::card::rage(),
+            library::card::shockwave(),
+            library::card::firebomb(),
+            library::card::spit(),
+            library::card::debug_debuff(),
+        ],
+        torso: vec![
+            library::card::heal(),
+            library::card::flurry(),
+            library::card::leap(),
+            library::card::sweep(),
+        ],
+        arm_l: vec![
+            library::card::throw_debris(),
+            library::card::punch(),
+            library::card::guard(),
+        ],
+        arm_r: vec![
+            library::card::smash(),
+            library::card::shove(),
+            library::card::grab(),
+        ],
+        leg_l: vec![library::card::stagger()],
+        leg_r: vec![library::card::caltrops()],
+    })
+}
+
+struct Loadout {
+    head: Vec<Card>,
+    torso: Vec<Card>,
+    arm_l: Vec<Card>,
+    arm_r: Vec<Card>,
+    leg_l: Vec<Card>,
+    leg_r: Vec<Card>,
+}
+
+fn body(loadout: Loadout) -> Creature {
+    let head = with_cards(Part {
         thought: 3,
         memory: 5,
         ..Part::new(
             "Head",
             &[PartTag::Head, PartTag::Flesh, PartTag::Vital, PartTag::Open],
             20)
-    };
-    let torso = Part {
-        cards: IdMap::from_iter(vec![library::card::heal()]),
+    }, loadout.head);
+    let torso = with_cards(Part::new(
+        "Torso",
+        &[PartTag::Torso, PartTag::Flesh, PartTag::Vital, PartTag::Open],
+        50,
+    ), loadout.torso);
+    let arm_l = with_cards(Part {
+        power: 1,
         ..Part::new(
-            "Torso",
-            &[PartTag::Torso, PartTag::Flesh, PartTag::Vital, PartTag::Open],
-            50)
-    };
-    let arm_l = Part {
-        cards: IdMap::from_iter(vec![
-            library::card::throw_debris(),
-            library::card::punch(),
-            library::card::guard(),
-        ]),
+            "Arm",
+            &[PartTag::Limb, PartTag::Flesh, PartTag::Arm, PartTag::Open],
+            30)
+    }, loadout.arm_l);
+    // The good arm.
+    let arm_r = with_cards(Part {
+        power: 2,
         ..Part::new(
             "Arm",
             &[PartTag::Limb, PartTag::Flesh, PartTag::Arm, PartTag::Open],
             30)
-    };
-    let arm_r = arm_l.clone();
-    let leg_l = Part {
-        cards: IdMap::from_iter(vec![library::card::stagger()]),
+    }, loadout.arm_r);
+    let leg = Part {
         mp: 1,
+        capacity: 1,
         ..Part::new(
             "Leg",
             &[PartTag::Limb, PartTag::Flesh, PartTag::Leg, PartTag::Open],
             30)
     };
-    let leg_r = leg_l.clone();
-    Creature::new("Player", &[head, torso, arm_l, arm_r, leg_l, leg_r], None)
-}
\ No newline at end of file
+    let leg_l = with_cards(leg.clone(), loadout.leg_l);
+    let leg_r = with_cards(leg, loadout.leg_r);
+
+    let mut parts = IdMap::new();
+    let head = parts.add(head);
+    let torso = parts.add(torso);
+    let arm_l = parts.add(arm_l);
+    let arm_r = parts.add(arm_r);
+    let leg_l = parts.add(leg_l);
+    let leg_r = parts.add(leg_r);
+    parts.get_mut(torso).unwrap().joints = vec![
+        Joint::new(&[PartTag::Head], Some(head)),
+        Joint::new(&[PartTag::Arm], Some(arm_l)),
+        Joint::new(&[PartTag::Arm], Some(arm_r)),
+        Joint::new(&[PartTag::Leg], Some(leg_l)),
+        Joint::new(&[PartTag::Leg], Some(leg_r)),
+    ];
+    Creature::new_ids("Player", parts, None)
+}
+
+// Capacity grows to fit, so that a loadout can carry more than a part would
+// normally hold.
+fn with_cards(mut part: Part, cards: Vec<Card>) -> Part {
+    part.capacity = std::cmp::max(part.capacity, cards.len() as i32);
+    for card in cards {
+        part.add_card(card).expect("part over capacity");
+    }
+    part
+}
@@ -1,24 +1,46 @@
-use std::collections::{
-    hash_map::{Entry, VacantEntry},
-    HashMap, HashSet, VecDeque,
+use std::{
+    cell::RefCell,
+    cmp::Ordering,
+    collections::{
+        hash_map::{Entry, VacantEntry},
+        BinaryHeap, HashMap, HashSet,
+    },
 };
 use fnv::FnvHashSet;
 use hex::Hex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use ts_data_derive::TsData;
-use wasm_bindgen::prelude::wasm_bindgen;
 use crate::{
     creature::Creature,
     error::{Error, Result},
     id_map::Id,
+    serde_pairs,
     some_or,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Map {
     // TASK: use bracket-lib?
+    #[serde(with = "serde_pairs")]
     tiles: HashMap<Hex, Tile>,
+    // Where each creature stands; see `occupied` for everything it covers.
     creatures: HashMap<Id<Creature>, Hex>,
+    #[serde(default)]
+    footprints: HashMap<Id<Creature>, Footprint>,
+    // How `los_from` works out sight.
+    #[serde(default)]
+    fov: Fov,
+    // Sight only changes when something moves, so it's kept until then.
+    #[serde(skip)]
+    los_cache: LosCache,
+}
+
+// Copies start with an empty cache; they're usually made to be changed.
+#[derive(Debug, Default)]
+struct LosCache(RefCell<HashMap<(Hex, Id<Creature>, Fov), HashSet<Hex>>>);
+
+impl Clone for LosCache {
+    fn clone(&self) -> Self { LosCache::default() }
 }
 
 impl Map {
@@ -30,9 +52,20 @@ impl Map {
                 2 if coord.x % 2 == 0 && coord.y % 2 == 0 => Space::Wall,
                 _ => Space::Empty,
             };
-            tiles.insert(coord, Tile { space, creature: None });
+            tiles.insert(coord, Tile::new(space));
+        }
+        Map::from_tiles(tiles)
+    }
+
+    /// An empty map with the given layout; see `MapGen` for making one.
+    pub fn from_tiles(tiles: HashMap<Hex, Tile>) -> Self {
+        Map {
+            tiles,
+            creatures: HashMap::new(),
+            footprints: HashMap::new(),
+            fov: Fov::default(),
+            los_cache: LosCache::default(),
         }
-        Map { tiles, creatures: HashMap::new() }
     }
 
     // Accessors
@@ -45,41 +78,64 @@ impl Map {
         &self.creatures
     }
 
-    pub fn range_from(&self, start: Hex, range: i32, space_only: bool) -> HashSet<Hex> {
-        let mut out = HashSet::new();
-        let mut pending: VecDeque<(Hex, i32)> = VecDeque::new();
-        pending.push_back((start, range));
-        while !pending.is_empty() {
-            let (current, remaining_range) = pending.pop_front().unwrap();
-            if !out.insert(current) { continue }
-            if remaining_range > 0 {
-                for hex in current.neighbors() {
-                    if out.contains(&hex) { continue }
-                    let tile = some_or!(self.tiles.get(&hex), continue);
-                    let open = if space_only {
-                        matches!(tile, Tile { space: Space::Empty, .. })
-                    } else { tile.is_open() };
-                    if open {
-                        pending.push_back((hex, remaining_range-1));
-                    }
-                }
-            }
+    pub fn fov(&self) -> Fov { self.fov }
+
+    pub fn footprint(&self, id: Id<Creature>) -> Footprint {
+        self.footprints.get(&id).cloned().unwrap_or_default()
+    }
+
+    /// Every hex creature `id` covers.
+    pub fn occupied(&self, id: Id<Creature>) -> Vec<Hex> {
+        match self.creatures.get(&id) {
+            Some(&at) => self.footprint(id).at(at),
+            None => vec![],
         }
-        out
     }
 
+    /// MP for creature `id` to step so it stands at `at`: the dearest tile
+    /// it would cover, or `None` if it doesn't fit there.
+    pub fn footprint_cost(&self, id: Id<Creature>, at: Hex) -> Option<i32> {
+        self.cover_cost(id, at, true)
+    }
+
+    /// How far apart `id` would be from creature `other` if it stood at
+    /// `at`; 1 is adjacent.
+    pub fn gap(&self, id: Id<Creature>, at: Hex, other: Id<Creature>) -> Option<i32> {
+        let theirs = self.occupied(other);
+        self.footprint(id).at(at).into_iter()
+            .flat_map(|a| theirs.iter().map(move |b| a.distance_to(*b)))
+            .min()
+    }
+
+    /// Hexes creature `id` could get to with `range` MP, paying for terrain
+    /// under its whole footprint.  With `space_only`, other creatures don't
+    /// block the way.
+    pub fn range_from(&self, id: Id<Creature>, range: i32, space_only: bool) -> HashSet<Hex> {
+        let &start = some_or!(self.creatures.get(&id), return HashSet::new());
+        let costs = self.distances(vec![start], |hex, _| self.cover_cost(id, hex, !space_only));
+        costs.iter()
+            .filter(|&(_, c)| c <= range)
+            .map(|(h, _)| h)
+            .collect()
+    }
+
+    /// What `id` can see from `start`.  Walls hide what's behind them and are
+    /// never in the result; other creatures are visible but block the view.
     pub fn los_from(&self, start: Hex, id: Id<Creature>) -> HashSet<Hex> {
-        let mut out = HashSet::new();
-        out.insert(start);
-        for (hex, tile) in &self.tiles {
-            if *hex == start { continue; }
-            if tile.space != Space::Empty { continue; }
-            if self.can_see(start, *hex, id) {
-                out.insert(*hex);
-            }
-        }
+        self.los_with(start, id, self.fov)
+    }
 
-        out
+    pub fn los_with(&self, start: Hex, id: Id<Creature>, fov: Fov) -> HashSet<Hex> {
+        let key = (start, id, fov);
+        if let Some(los) = self.los_cache.0.borrow().get(&key) {
+            return los.clone();
+        }
+        let los = match fov {
+            Fov::Shadowcast => self.shadowcast(start, id),
+            Fov::DualLine => self.dual_line(start, id),
+        };
+        self.los_cache.0.borrow_mut().insert(key, los.clone());
+        los
     }
 
     pub fn los_of(&self, id: Id<Creature>) -> Option<HashSet<Hex>> {
@@ -92,56 +148,235 @@ impl Map {
         if !to_tile.is_open() {
             return Err(Error::Obstructed);
         }
-        let tiles = &self.tiles;
-        let neighbors = |hex: Hex| -> Vec<Hex> {
-            let mut out = vec![];
-            for neighbor in hex.neighbors() {
-                if !tiles.get(&neighbor).map(|t| t.is_open()).unwrap_or(false) {
-                    continue;
+        self.path_with(from, to, |_, tile| tile.walk_cost())
+    }
+
+    /// The cheapest path for creature `id` to end up standing at `to`,
+    /// keeping its whole footprint on open ground.
+    pub fn path_for(&self, id: Id<Creature>, to: Hex) -> Result<Vec<Hex>> {
+        let &from = self.creatures.get(&id).ok_or(Error::NoSuchCreature)?;
+        if !self.tiles.contains_key(&to) { return Err(Error::OutOfBounds); }
+        if self.footprint_cost(id, to).is_none() { return Err(Error::Obstructed); }
+        self.path_with(from, to, |hex, _| self.footprint_cost(id, hex))
+    }
+
+    /// The cheapest path from `from` to `to`, where `cost` gives the price of
+    /// stepping onto a tile, or `None` if it can't be entered.
+    pub fn path_with<F>(&self, from: Hex, to: Hex, cost: F) -> Result<Vec<Hex>>
+        where F: Fn(Hex, &Tile) -> Option<i32>
+    {
+        if !self.tiles.contains_key(&to) { return Err(Error::OutOfBounds); }
+        let neighbors = |hex: Hex| self.neighbor_costs(hex, &cost);
+        a_star(from, to, neighbors).ok_or(Error::Obstructed)
+    }
+
+    /// The cost of the cheapest path from any of `goals` to every reachable
+    /// hex; `cost` is as for `path_with`.  Building one of these once is much
+    /// cheaper than pathing to many hexes one at a time.
+    pub fn distances<I, F>(&self, goals: I, cost: F) -> DistanceMap
+        where I: IntoIterator<Item=Hex>, F: Fn(Hex, &Tile) -> Option<i32>
+    {
+        let mut out = HashMap::new();
+        let mut open = BinaryHeap::new();
+        for goal in goals {
+            if !self.tiles.contains_key(&goal) { continue; }
+            out.insert(goal, 0);
+            open.push(Scored { score: 0, hex: goal });
+        }
+        while let Some(Scored { score, hex }) = open.pop() {
+            if out.get(&hex).map_or(false, |&s| s < score) { continue; }
+            for (neighbor, step) in self.neighbor_costs(hex, &cost) {
+                let next = score + step;
+                if out.get(&neighbor).map_or(true, |&s| next < s) {
+                    out.insert(neighbor, next);
+                    open.push(Scored { score: next, hex: neighbor });
                 }
-                out.push(neighbor)
-            }
-            out
-        };
-        let path: Vec<_> = match a_star(from, to, neighbors) {
-            Some(p) => p,
-            None => return Err(Error::Obstructed),
-        };
-        for coord in path.iter().skip(1) {
-            let tile = self.tiles.get(coord).ok_or(Error::OutOfBounds)?;
-            if !tile.is_open() {
-                return Err(Error::Obstructed);
             }
         }
-        Ok(path)
+        DistanceMap { costs: out }
+    }
+
+    /// MP `id` needs to walk `path`, which starts where it already is, or
+    /// `None` if some step can't be taken.
+    pub fn path_cost(&self, id: Id<Creature>, path: &[Hex]) -> Option<i32> {
+        path.iter().skip(1)
+            .map(|&h| self.footprint_cost(id, h))
+            .sum()
     }
 
     // Mutators
 
+    /// Picks how `los_from` works out sight from now on.
+    pub fn set_fov(&mut self, fov: Fov) {
+        self.fov = fov;
+    }
+
     pub fn place_at(&mut self, creature_id: Id<Creature>, at: Hex) -> Result<()> {
-        let tile = self.tiles.get_mut(&at).ok_or(Error::OutOfBounds)?;
-        if tile.creature.is_some() { return Err(Error::Obstructed) }
+        self.place(creature_id, at, Footprint::single())
+    }
+
+    pub fn place(&mut self, creature_id: Id<Creature>, at: Hex, footprint: Footprint) -> Result<()> {
+        let hexes = footprint.at(at);
+        for hex in &hexes {
+            let tile = self.tiles.get(hex).ok_or(Error::OutOfBounds)?;
+            if tile.creature.is_some() { return Err(Error::Obstructed) }
+        }
         let c_ent = vacant_or(self.creatures.entry(creature_id), Error::Obstructed)?;
-        tile.creature = Some(creature_id);
         c_ent.insert(at);
+        for hex in &hexes {
+            self.tiles.get_mut(hex).unwrap().creature = Some(creature_id);
+        }
+        self.footprints.insert(creature_id, footprint);
+        self.los_cache.0.get_mut().clear();
+        Ok(())
+    }
+
+    /// Damages the object at `hex`, removing it if that destroys it; returns
+    /// the hp it has left.
+    pub fn hit_object(&mut self, hex: Hex, damage: i32) -> Result<i32> {
+        let tile = self.tiles.get_mut(&hex).ok_or(Error::OutOfBounds)?;
+        let left = match &mut tile.object {
+            Some(Object::Crate { hp }) | Some(Object::Barricade { hp }) => {
+                *hp -= damage;
+                *hp
+            }
+            _ => return Err(Error::InvalidTarget),
+        };
+        if left <= 0 { tile.object = None; }
+        self.los_cache.0.get_mut().clear();
+        Ok(left)
+    }
+
+    /// What happens to the object at `hex` when someone steps there: doors
+    /// open and pickups are taken.  Returns the object as it was.
+    pub fn enter_object(&mut self, hex: Hex) -> Option<Object> {
+        let tile = self.tiles.get_mut(&hex)?;
+        let before = tile.object.clone()?;
+        match &mut tile.object {
+            Some(Object::Door { open }) => *open = true,
+            Some(Object::Pickup { .. }) => tile.object = None,
+            _ => (),
+        }
+        self.los_cache.0.get_mut().clear();
+        Some(before)
+    }
+
+    pub fn set_object(&mut self, hex: Hex, object: Option<Object>) -> Result<()> {
+        self.tiles.get_mut(&hex).ok_or(Error::OutOfBounds)?.object = object;
+        self.los_cache.0.get_mut().clear();
         Ok(())
     }
 
     pub fn move_to(&mut self, creature_id: Id<Creature>, to: Hex) -> Result<()> {
-        let from: &Hex = self.creatures.get(&creature_id).ok_or(Error::NoSuchCreature)?;
-        self.tiles.get_mut(&to).ok_or(Error::OutOfBounds)?.creature = Some(creature_id);
-        self.tiles.get_mut(from).unwrap().creature = None;
+        let from = *self.creatures.get(&creature_id).ok_or(Error::NoSuchCreature)?;
+        if from == to { return Ok(()) }
+        if !self.tiles.contains_key(&to) { return Err(Error::OutOfBounds) }
+        if self.footprint_cost(creature_id, to).is_none() { return Err(Error::Obstructed) }
+        let footprint = self.footprint(creature_id);
+        for hex in footprint.at(from) {
+            self.tiles.get_mut(&hex).unwrap().creature = None;
+        }
+        for hex in footprint.at(to) {
+            self.tiles.get_mut(&hex).unwrap().creature = Some(creature_id);
+        }
         self.creatures.insert(creature_id, to);
+        self.los_cache.0.get_mut().clear();
         Ok(())
     }
 
     // Private
 
+    // As `footprint_cost`, but other creatures only get in the way if
+    // `creatures_block`.
+    fn cover_cost(&self, id: Id<Creature>, at: Hex, creatures_block: bool) -> Option<i32> {
+        let mut out = 0;
+        for hex in self.footprint(id).at(at) {
+            let tile = self.tiles.get(&hex)?;
+            if creatures_block && tile.creature.map_or(false, |c| c != id) { return None; }
+            out = std::cmp::max(out, tile.ground_cost()?);
+        }
+        Some(out)
+    }
+
+    fn neighbor_costs<F>(&self, hex: Hex, cost: &F) -> Vec<(Hex, i32)>
+        where F: Fn(Hex, &Tile) -> Option<i32>
+    {
+        let mut out = vec![];
+        for neighbor in hex.neighbors() {
+            let tile = some_or!(self.tiles.get(&neighbor), continue);
+            let c = some_or!(cost(neighbor, tile), continue);
+            out.push((neighbor, c));
+        }
+        out
+    }
+
+    fn blocks_view(&self, hex: Hex, id: Id<Creature>) -> bool {
+        match self.tiles.get(&hex) {
+            Some(tile) if tile.blocks_sight() => true,
+            Some(Tile { creature: Some(cid), .. }) => *cid != id,
+            _ => false,
+        }
+    }
+
+    // Unlike walls, objects that block sight can themselves be seen.
+    fn visible(&self, hex: Hex) -> bool {
+        self.tiles.get(&hex).map_or(false, |t| !t.space.blocks_sight())
+    }
+
+    // Symmetric shadowcasting, after Albert Ford's, with each sextant of the
+    // hex grid scanned as rows of increasing distance.  A tile is seen only if
+    // its center is, so `a` sees `b` exactly when `b` sees `a`.
+    fn shadowcast(&self, start: Hex, id: Id<Creature>) -> HashSet<Hex> {
+        let mut out = HashSet::new();
+        out.insert(start);
+        let radius = self.tiles.keys().map(|h| h.distance_to(start)).max().unwrap_or(0);
+        for sextant in 0..6 {
+            let mut rows = vec![Row { depth: 1, start: Slope(0, 1), end: Slope(1, 1) }];
+            while let Some(mut row) = rows.pop() {
+                if row.depth > radius { continue; }
+                let mut prev_blocked = None;
+                for ix in row.first()..=row.last() {
+                    let hex = sextant_hex(start, sextant, row.depth, ix);
+                    let blocked = self.blocks_view(hex, id);
+                    if row.centered(ix) && self.visible(hex) {
+                        out.insert(hex);
+                    }
+                    if prev_blocked == Some(true) && !blocked {
+                        row.start = Slope::edge(row.depth, ix);
+                    }
+                    if prev_blocked == Some(false) && blocked {
+                        rows.push(Row { depth: row.depth + 1, start: row.start, end: Slope::edge(row.depth, ix) });
+                    }
+                    prev_blocked = Some(blocked);
+                }
+                if prev_blocked == Some(false) {
+                    rows.push(Row { depth: row.depth + 1, ..row });
+                }
+            }
+        }
+        out
+    }
+
+    // The original rule: a tile is visible if either of the two lines that
+    // split the difference on ties gets there unobstructed.
+    fn dual_line(&self, start: Hex, id: Id<Creature>) -> HashSet<Hex> {
+        let mut out = HashSet::new();
+        out.insert(start);
+        for (hex, tile) in &self.tiles {
+            if *hex == start { continue; }
+            if tile.space.blocks_sight() { continue; }
+            if self.can_see(start, *hex, id) {
+                out.insert(*hex);
+            }
+        }
+        out
+    }
+
     fn all_clear(&self, between: &[Hex], id: Id<Creature>) -> bool {
         between.into_iter()
             .filter_map(|coord| self.tiles.get(&coord))
             .all(|line_tile| match line_tile {
-                Tile { space: Space::Wall, .. } => false,
+                tile if tile.blocks_sight() => false,
                 Tile { creature: Some(cid), .. } if *cid != id => false,
                 _ => true,
             })
@@ -157,45 +392,31 @@ impl Map {
 }
 
 fn a_star<F>(start: Hex, goal: Hex, neighbors: F) -> Option<Vec<Hex>>
-    where F: Fn(Hex) -> Vec<Hex>
+    where F: Fn(Hex) -> Vec<(Hex, i32)>
 {
-    let mut open_set = FnvHashSet::default();
-    open_set.insert(start);
+    let mut open_set = BinaryHeap::new();
+    open_set.push(Scored { score: start.distance_to(goal), hex: start });
 
     let mut came_from: HashMap<Hex, Hex> = HashMap::new();
 
     let mut g_score: HashMap<Hex, i32> = HashMap::new();
     g_score.insert(start, 0);
 
-    let mut f_score: HashMap<Hex, i32> = HashMap::new();
-    f_score.insert(start, start.distance_to(goal));
+    let mut closed = FnvHashSet::default();
 
-    while !open_set.is_empty() {
-        let mut current = None;
-        for &coord in &open_set {
-            let f = f_score.get(&coord).unwrap_or(&std::i32::MAX);
-            match current {
-                None => {
-                    current = Some((coord, f));
-                }
-                Some((_, other_f)) if f < other_f => {
-                    current = Some((coord, f));
-                }
-                _ => (),
-            }
-        }
-        let (current, _) = current.unwrap();
+    while let Some(Scored { hex: current, .. }) = open_set.pop() {
         if current == goal {
             return Some(reconstruct_path(came_from, current))
         }
-        open_set.remove(&current);
-        for neighbor in neighbors(current) {
-            let tentative_g_score = g_score.get(&current).unwrap() + 1;
+        // Stale entries are left in the heap rather than updated in place.
+        if !closed.insert(current) { continue; }
+        let current_g = g_score[&current];
+        for (neighbor, cost) in neighbors(current) {
+            let tentative_g_score = current_g + cost;
             if tentative_g_score < *g_score.get(&neighbor).unwrap_or(&std::i32::MAX) {
                 came_from.insert(neighbor, current);
                 g_score.insert(neighbor, tentative_g_score);
-                f_score.insert(neighbor, tentative_g_score + neighbor.distance_to(goal));
-                open_set.insert(neighbor);
+                open_set.push(Scored { score: tentative_g_score + neighbor.distance_to(goal), hex: neighbor });
             }
         }
     }
@@ -203,6 +424,134 @@ fn a_star<F>(start: Hex, goal: Hex, neighbors: F) -> Option<Vec<Hex>>
     None
 }
 
+// A hex ordered so that `BinaryHeap` pops the lowest score first.  Ties go by
+// position so the search doesn't depend on hash order.
+#[derive(Debug, PartialEq, Eq)]
+struct Scored {
+    score: i32,
+    hex: Hex,
+}
+
+impl Ord for Scored {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (other.score, other.hex.y, other.hex.x).cmp(&(self.score, self.hex.y, self.hex.x))
+    }
+}
+
+impl PartialOrd for Scored {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+/// The hexes a creature covers, as offsets from the hex it stands on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Footprint(pub Vec<(i32, i32)>);
+
+impl Footprint {
+    pub fn single() -> Self { Footprint(vec![(0, 0)]) }
+
+    /// Everything within `radius` of the center.
+    pub fn radius(radius: i32) -> Self {
+        Footprint(hex::ORIGIN.area(radius).into_iter().map(|h| (h.x, h.y)).collect())
+    }
+
+    pub fn at(&self, anchor: Hex) -> Vec<Hex> {
+        self.0.iter().map(|&(x, y)| Hex { x: anchor.x + x, y: anchor.y + y }).collect()
+    }
+}
+
+impl Default for Footprint {
+    fn default() -> Self { Footprint::single() }
+}
+
+/// How line of sight is worked out.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum Fov {
+    /// Fast; the default.
+    Shadowcast,
+    /// Traces two lines to every tile and accepts either; slow on big maps.
+    DualLine,
+}
+
+impl Default for Fov {
+    fn default() -> Self { Fov::Shadowcast }
+}
+
+/// The one of the six directions out of `from` that heads most nearly toward
+/// `to`, as an offset; `None` if they're the same hex.
+pub fn direction(from: Hex, to: Hex) -> Option<(i32, i32)> {
+    let dist = from.distance_to(to);
+    if dist == 0 { return None; }
+    DIRECTIONS.iter().cloned()
+        .min_by_key(|&(dx, dy)| Hex { x: from.x + dx*dist, y: from.y + dy*dist }.distance_to(to))
+}
+
+// The axial directions, in order around the ring.
+const DIRECTIONS: [(i32, i32); 6] = [(1, 0), (1, -1), (0, -1), (-1, 0), (-1, 1), (0, 1)];
+
+// Hex `ix` of the row `depth` away from `center` in `sextant`; `ix` runs from 0
+// to `depth`, and the last hex of one sextant is the first of the next.
+fn sextant_hex(center: Hex, sextant: usize, depth: i32, ix: i32) -> Hex {
+    let (cx, cy) = DIRECTIONS[(sextant + 4) % 6];
+    let (dx, dy) = DIRECTIONS[sextant];
+    Hex { x: center.x + cx*depth + dx*ix, y: center.y + cy*depth + dy*ix }
+}
+
+// A fraction of the way across a sextant, kept exact so ties round the same
+// way every time.
+#[derive(Debug, Copy, Clone)]
+struct Slope(i32, i32);
+
+impl Slope {
+    // The leading edge of hex `ix` in a row.
+    fn edge(depth: i32, ix: i32) -> Slope { Slope(2*ix - 1, 2*depth) }
+}
+
+#[derive(Debug, Copy, Clone)]
+struct Row {
+    depth: i32,
+    start: Slope,
+    end: Slope,
+}
+
+impl Row {
+    // depth * start, rounding ties up.
+    fn first(&self) -> i32 {
+        let Slope(n, d) = self.start;
+        std::cmp::max(0, (2*self.depth*n + d).div_euclid(2*d))
+    }
+
+    // depth * end, rounding ties down.
+    fn last(&self) -> i32 {
+        let Slope(n, d) = self.end;
+        let floored = (2*self.depth*n - d).div_euclid(2*d);
+        let exact = (2*self.depth*n - d).rem_euclid(2*d) == 0;
+        std::cmp::min(self.depth, if exact { floored } else { floored + 1 })
+    }
+
+    fn centered(&self, ix: i32) -> bool {
+        let (Slope(sn, sd), Slope(en, ed)) = (self.start, self.end);
+        ix*sd >= self.depth*sn && ix*ed <= self.depth*en
+    }
+}
+
+/// Path costs to every reachable hex from a set of goals; see
+/// `Map::distances`.
+#[derive(Debug, Clone)]
+pub struct DistanceMap {
+    costs: HashMap<Hex, i32>,
+}
+
+impl DistanceMap {
+    pub fn get(&self, hex: Hex) -> Option<i32> {
+        self.costs.get(&hex).cloned()
+    }
+
+    /// Every reachable hex with its cost, in no particular order.
+    pub fn iter(&self) -> impl Iterator<Item=(Hex, i32)> + '_ {
+        self.costs.iter().map(|(h, c)| (*h, *c))
+    }
+}
+
 fn reconstruct_path(came_from: HashMap<Hex, Hex>, candidate: Hex) -> Vec<Hex> {
     let mut out = vec![];
     out.push(candidate);
@@ -222,23 +571,206 @@ fn vacant_or<K, V, E>(e: Entry<K, V>, err: E) -> std::result::Result<VacantEntry
     }
 }
 
-#[derive(Debug, Clone, PartialEq, Eq, Serialize, TsData)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TsData)]
 pub struct Tile {
     pub space: Space,
     pub creature: Option<Id<Creature>>,
+    #[serde(default)]
+    pub object: Option<Object>,
 }
 
 impl Tile {
+    pub fn new(space: Space) -> Self {
+        Tile { space, creature: None, object: None }
+    }
+
+    /// Can a creature step onto this tile?
     pub fn is_open(&self) -> bool {
+        self.walk_cost().is_some()
+    }
+
+    /// MP to step onto this tile, if it can be stepped onto at all.
+    pub fn walk_cost(&self) -> Option<i32> {
+        if self.creature.is_some() { return None; }
+        self.ground_cost()
+    }
+
+    /// As `walk_cost`, ignoring whoever's standing here.
+    pub fn ground_cost(&self) -> Option<i32> {
+        if self.object.as_ref().map_or(false, |o| o.blocks_movement()) { return None; }
+        self.space.cost()
+    }
+
+    pub fn blocks_sight(&self) -> bool {
+        self.space.blocks_sight() || self.object.as_ref().map_or(false, |o| o.blocks_sight())
+    }
+}
+
+/// Something sitting on a tile that isn't a creature.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, TsData)]
+pub enum Object {
+    /// Low enough to see over; can be smashed.
+    Crate { hp: i32 },
+    /// A wall that can be knocked down.
+    Barricade { hp: i32 },
+    /// Opens when someone walks through, and stays open.
+    Door { open: bool },
+    /// Used up by the first creature to step on it.
+    Pickup { effect: Pickup },
+}
+
+impl Object {
+    pub fn blocks_movement(&self) -> bool {
         match self {
-            Tile { space: Space::Empty, creature: None } => true,
-            _ => false,
+            Object::Crate { .. } | Object::Barricade { .. } => true,
+            Object::Door { .. } | Object::Pickup { .. } => false,
         }
     }
+
+    pub fn blocks_sight(&self) -> bool {
+        match self {
+            Object::Barricade { .. } => true,
+            Object::Door { open } => !open,
+            Object::Crate { .. } | Object::Pickup { .. } => false,
+        }
+    }
+
+    /// Can this be hit?
+    pub fn hp(&self) -> Option<i32> {
+        match self {
+            Object::Crate { hp } | Object::Barricade { hp } => Some(*hp),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, TsData)]
+pub enum Pickup {
+    /// Heals every part of whoever takes it.
+    Heal { hp: i32 },
+    GainAP { ap: i32 },
+    GainMP { mp: i32 },
 }
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, TsData)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, TsData)]
 pub enum Space {
     Empty,
     Wall,
-}
\ No newline at end of file
+    Rough,
+    Water,
+    Pit,
+    // Burns whoever steps in; see `WorldExt::move_creature`.
+    Fire,
+}
+
+impl Space {
+    /// MP to step onto this space, or `None` if it can't be walked on.
+    pub fn cost(&self) -> Option<i32> {
+        match self {
+            Space::Empty | Space::Fire => Some(1),
+            Space::Rough => Some(2),
+            Space::Water => Some(3),
+            Space::Wall | Space::Pit => None,
+        }
+    }
+
+    pub fn blocks_sight(&self) -> bool {
+        *self == Space::Wall
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(x: i32, y: i32) -> Hex { Hex { x, y } }
+
+    // Anything with a clear straight line to it is in sight, whichever way
+    // the line's ties are broken.
+    fn clear_line(map: &Map, a: Hex, b: Hex) -> bool {
+        let clear = |line: Vec<Hex>| line.iter().all(|h| !map.tiles()[h].blocks_sight());
+        let mut one: Vec<Hex> = a.line_to(b).skip(1).collect();
+        one.pop();
+        let mut two: Vec<Hex> = a.line_to_alt(b).skip(1).collect();
+        two.pop();
+        clear(one) && clear(two)
+    }
+
+    // The modes don't agree exactly; they settle ties at the edges of what's
+    // in view differently.
+    #[test]
+    fn fov_modes_see_clear_lines_on_pillars() {
+        let mut map = crate::map_file::Layout::parse(include_str!("library/maps/pillars.txt")).unwrap().map;
+        let id = Id::synthesize(0);
+        let mut floor: Vec<Hex> = map.tiles().iter()
+            .filter(|(_, t)| !t.blocks_sight())
+            .map(|(h, _)| *h)
+            .collect();
+        floor.sort_by_key(|h| (h.y, h.x));
+        for &from in &floor {
+            let shadow = map.los_with(from, id, Fov::Shadowcast);
+            let dual = map.los_with(from, id, Fov::DualLine);
+            for &to in &floor {
+                if from.distance_to(to) <= 1 || clear_line(&map, from, to) {
+                    assert!(shadow.contains(&to), "shadowcast {:?} -> {:?}", from, to);
+                    assert!(dual.contains(&to), "dual line {:?} -> {:?}", from, to);
+                }
+            }
+            assert!(shadow.symmetric_difference(&dual).count() <= 2, "modes differ too much at {:?}", from);
+            for los in &[shadow, dual] {
+                assert!(los.iter().all(|h| !map.tiles()[h].space.blocks_sight()));
+            }
+        }
+        // The map setting picks which one `los_from` uses.
+        let from = floor[0];
+        map.set_fov(Fov::DualLine);
+        assert_eq!(map.los_from(from, id), map.los_with(from, id, Fov::DualLine));
+        map.set_fov(Fov::Shadowcast);
+        assert_eq!(map.los_from(from, id), map.los_with(from, id, Fov::Shadowcast));
+    }
+
+    #[test]
+    fn clones_start_with_an_empty_cache() {
+        let map = Map::new();
+        map.los_from(hex(0, 0), Id::synthesize(0));
+        assert!(!map.los_cache.0.borrow().is_empty());
+        assert!(map.clone().los_cache.0.borrow().is_empty());
+    }
+
+    #[test]
+    fn big_creature_moves_and_ranges() {
+        // Two hexes wide, on the default map; (2, 0) is a pillar.
+        let mut map = Map::new();
+        let id = Id::synthesize(0);
+        map.place(id, hex(0, 0), Footprint(vec![(0, 0), (1, 0)])).unwrap();
+        assert_eq!(map.occupied(id), vec![hex(0, 0), hex(1, 0)]);
+
+        // Stepping east would put its far half in the pillar; stepping west
+        // overlaps only itself.
+        assert_eq!(map.footprint_cost(id, hex(1, 0)), None);
+        assert_eq!(map.footprint_cost(id, hex(-1, 0)), Some(1));
+        let range = map.range_from(id, 1, false);
+        assert!(!range.contains(&hex(1, 0)));
+        assert!(range.contains(&hex(-1, 0)));
+
+        let path = map.path_for(id, hex(-1, 0)).unwrap();
+        assert_eq!(map.path_cost(id, &path), Some(1));
+        map.move_to(id, hex(-1, 0)).unwrap();
+        assert_eq!(map.tiles()[&hex(1, 0)].creature, None);
+        assert_eq!(map.tiles()[&hex(-1, 0)].creature, Some(id));
+        assert_eq!(map.tiles()[&hex(0, 0)].creature, Some(id));
+    }
+
+    #[test]
+    fn others_block_big_creatures() {
+        let mut map = Map::new();
+        let big = Id::synthesize(0);
+        let small = Id::synthesize(1);
+        map.place(big, hex(0, 0), Footprint(vec![(0, 0), (1, 0)])).unwrap();
+        map.place_at(small, hex(-2, 1)).unwrap();
+        // Moving to (-3, 1) would cover (-2, 1).
+        assert_eq!(map.footprint_cost(big, hex(-3, 1)), None);
+        assert!(!map.range_from(big, 5, false).contains(&hex(-3, 1)));
+        assert!(map.range_from(big, 5, true).contains(&hex(-3, 1)));
+    }
+}
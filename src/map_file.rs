@@ -0,0 +1,216 @@
+//! A plain text format for hand-built arenas.  Each line is a row of hexes,
+//! offset by half a hex per row as in the terminal client:
+//!
+//! ```text
+//!   # # #
+//!  # . m #
+//! # . P . #
+//!  # . . #
+//!   # # #
+//! ```
+//!
+//! `#` is a wall, `.` is floor, `%` rough ground, `~` water, `O` a pit and `^`
+//! fire.  `=` is a crate, `X` a barricade, `+` a closed door, `/` an open one
+//! and `*` a healing pickup, all on floor.  Letters are floor with something
+//! starting there (see `Spawn`); spaces are off the map.  Hexes only sit on
+//! every other column, alternating by row.  The top row is y = 0.
+
+use std::collections::HashMap;
+
+use hex::Hex;
+
+use crate::{
+    error::{Error, Result},
+    map::{Map, Object, Pickup, Space, Tile},
+};
+
+/// What starts on a spawn tile.
+#[derive(Debug, Copy, Clone, PartialEq, Eq)]
+pub enum Spawn {
+    Player,
+    Monopod,
+    Spitter,
+}
+
+impl Spawn {
+    pub fn symbol(&self) -> char {
+        match self {
+            Spawn::Player => 'P',
+            Spawn::Monopod => 'm',
+            Spawn::Spitter => 's',
+        }
+    }
+
+    pub fn from_symbol(c: char) -> Option<Spawn> {
+        match c {
+            'P' => Some(Spawn::Player),
+            'm' => Some(Spawn::Monopod),
+            's' => Some(Spawn::Spitter),
+            _ => None,
+        }
+    }
+}
+
+/// A map plus where everything starts on it.
+#[derive(Debug, Clone)]
+pub struct Layout {
+    pub map: Map,
+    pub spawns: Vec<(Spawn, Hex)>,
+}
+
+impl Layout {
+    pub fn new(map: Map) -> Self {
+        Layout { map, spawns: vec![] }
+    }
+
+    pub fn parse(text: &str) -> Result<Layout> {
+        let mut tiles = HashMap::new();
+        let mut spawns = vec![];
+        let mut player = false;
+        for (row, line) in text.lines().enumerate() {
+            let y = row as i32;
+            for (col, ch) in line.chars().enumerate() {
+                if ch == ' ' { continue; }
+                let err = |message: String| Error::Parse { line: row + 1, column: col + 1, message };
+                let col = col as i32;
+                if (col - y).rem_euclid(2) != 0 {
+                    return Err(err(format!("'{}' is between hexes", ch)));
+                }
+                let hex = Hex { x: (col - y) / 2, y };
+                if let Some(object) = object_from_symbol(ch) {
+                    tiles.insert(hex, Tile { object: Some(object), ..Tile::new(Space::Empty) });
+                    continue;
+                }
+                let space = match (ch, Spawn::from_symbol(ch)) {
+                    ('#', _) => Space::Wall,
+                    ('.', _) => Space::Empty,
+                    ('%', _) => Space::Rough,
+                    ('~', _) => Space::Water,
+                    ('O', _) => Space::Pit,
+                    ('^', _) => Space::Fire,
+                    (_, Some(spawn)) => {
+                        if spawn == Spawn::Player {
+                            if player { return Err(err("second player start".into())); }
+                            player = true;
+                        }
+                        spawns.push((spawn, hex));
+                        Space::Empty
+                    }
+                    (_, None) => return Err(err(format!("unknown tile '{}'", ch))),
+                };
+                tiles.insert(hex, Tile::new(space));
+            }
+        }
+        if !player {
+            return Err(Error::Parse {
+                line: std::cmp::max(1, text.lines().count()), column: 1,
+                message: "no player start".into(),
+            });
+        }
+        Ok(Layout { map: Map::from_tiles(tiles), spawns })
+    }
+
+    /// The inverse of `parse`, shifted so the top row is y = 0.
+    pub fn write(&self) -> String {
+        let tiles = self.map.tiles();
+        if tiles.is_empty() { return String::new(); }
+        let min_y = tiles.keys().map(|h| h.y).min().unwrap();
+        let max_y = tiles.keys().map(|h| h.y).max().unwrap();
+        let min_col = tiles.keys().map(|h| 2*h.x + h.y).min().unwrap();
+        let max_col = tiles.keys().map(|h| 2*h.x + h.y).max().unwrap();
+        // Shifting by whole hexes keeps each column's parity, so the leftmost
+        // hex may have to sit in the second column.
+        let first_col = min_col - (min_col - min_y).rem_euclid(2);
+        let spawns: HashMap<Hex, Spawn> = self.spawns.iter().map(|&(s, h)| (h, s)).collect();
+        let mut out = String::new();
+        for y in min_y..=max_y {
+            let mut line = String::new();
+            for col in first_col..=max_col {
+                if (col - y).rem_euclid(2) != 0 {
+                    line.push(' ');
+                    continue;
+                }
+                let hex = Hex { x: (col - y) / 2, y };
+                let ch = match (spawns.get(&hex), tiles.get(&hex)) {
+                    (Some(spawn), _) => spawn.symbol(),
+                    (None, Some(Tile { object: Some(object), .. })) => object_symbol(object),
+                    (None, Some(tile)) => match tile.space {
+                        Space::Wall => '#',
+                        Space::Empty => '.',
+                        Space::Rough => '%',
+                        Space::Water => '~',
+                        Space::Pit => 'O',
+                        Space::Fire => '^',
+                    },
+                    (None, None) => ' ',
+                };
+                line.push(ch);
+            }
+            out.push_str(line.trim_end());
+            out.push('\n');
+        }
+        out
+    }
+}
+
+const CRATE_HP: i32 = 10;
+const BARRICADE_HP: i32 = 20;
+const PICKUP_HEAL: i32 = 5;
+
+fn object_from_symbol(c: char) -> Option<Object> {
+    Some(match c {
+        '=' => Object::Crate { hp: CRATE_HP },
+        'X' => Object::Barricade { hp: BARRICADE_HP },
+        '+' => Object::Door { open: false },
+        '/' => Object::Door { open: true },
+        '*' => Object::Pickup { effect: Pickup::Heal { hp: PICKUP_HEAL } },
+        _ => return None,
+    })
+}
+
+/// How `object` is written; damage and the kind of pickup are lost.
+pub fn object_symbol(object: &Object) -> char {
+    match object {
+        Object::Crate { .. } => '=',
+        Object::Barricade { .. } => 'X',
+        Object::Door { open: false } => '+',
+        Object::Door { open: true } => '/',
+        Object::Pickup { .. } => '*',
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const PILLARS: &str = include_str!("library/maps/pillars.txt");
+
+    #[test]
+    fn write_round_trips() {
+        let layout = Layout::parse(PILLARS).unwrap();
+        let text = layout.write();
+        let again = Layout::parse(&text).unwrap();
+        assert_eq!(again.map.tiles(), layout.map.tiles());
+        assert_eq!(again.spawns, layout.spawns);
+        assert_eq!(again.write(), text);
+    }
+
+    #[test]
+    fn objects_round_trip() {
+        let text = "  # # #\n # = + #\n# * P / #\n # X ^ #\n  # # #\n";
+        let layout = Layout::parse(text).unwrap();
+        assert_eq!(layout.write(), text);
+    }
+
+    #[test]
+    fn errors_have_positions() {
+        match Layout::parse("# P\n # ?\n") {
+            Err(Error::Parse { line, column, .. }) => assert_eq!((line, column), (2, 4)),
+            other => panic!("{:?}", other),
+        }
+        match Layout::parse("# . #\n") {
+            Err(Error::Parse { message, .. }) => assert_eq!(message, "no player start"),
+            other => panic!("{:?}", other),
+        }
+    }
+}
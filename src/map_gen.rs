@@ -0,0 +1,221 @@
+use std::collections::{HashMap, HashSet};
+
+use hex::Hex;
+use rand::{Rng, seq::SliceRandom};
+
+use crate::{
+    error::{Error, Result},
+    map::{Footprint, Map, Space, Tile},
+    some_or,
+};
+
+/// Parameters for a generated arena; see `MapGen::generate`.
+#[derive(Debug, Clone)]
+pub struct MapGen {
+    // Outer radius, walls included.
+    pub radius: i32,
+    // Chunks taken out of the outline.
+    pub bites: i32,
+    // Obstacle clusters, each a random walk of `cluster_size` walls.
+    pub clusters: i32,
+    pub cluster_size: i32,
+    // Walls run across the arena, each with a single gap.
+    pub chokepoints: i32,
+    // Patches of rough ground, water or fire, each a random walk like the
+    // obstacle clusters.
+    pub patches: i32,
+    // Arenas with fewer open tiles than this are thrown away.
+    pub min_open: usize,
+}
+
+const MAX_ATTEMPTS: usize = 20;
+
+const PATCH_SPACES: &[Space] = &[Space::Rough, Space::Rough, Space::Water, Space::Fire];
+
+impl MapGen {
+    /// A connected arena: every open tile can be reached from every other.
+    /// Fails if no attempt comes out big enough.
+    pub fn generate<R: Rng>(&self, rng: &mut R) -> Result<Map> {
+        for _ in 0..MAX_ATTEMPTS {
+            if let Some(map) = self.attempt(rng) { return Ok(map); }
+        }
+        Err(Error::MapGen)
+    }
+
+    fn attempt<R: Rng>(&self, rng: &mut R) -> Option<Map> {
+        let rim = sorted(hex::ORIGIN.area(self.radius).into_iter()
+            .filter(|h| h.distance_to(hex::ORIGIN) == self.radius));
+
+        // Outline
+        let mut inside: HashSet<Hex> = hex::ORIGIN.area(self.radius).into_iter().collect();
+        for _ in 0..self.bites {
+            let &center = rim.choose(rng)?;
+            for hex in center.area(rng.gen_range(1, 3)) {
+                inside.remove(&hex);
+            }
+        }
+        let mut tiles = HashMap::new();
+        for &hex in &inside {
+            let space = if hex.neighbors().all(|n| inside.contains(&n)) {
+                Space::Empty
+            } else {
+                Space::Wall
+            };
+            tiles.insert(hex, Tile::new(space));
+        }
+
+        // Obstacles
+        for _ in 0..self.clusters {
+            let mut at = *open_tiles(&tiles).choose(rng)?;
+            for _ in 0..self.cluster_size {
+                wall(&mut tiles, at);
+                let next = sorted(at.neighbors().filter(|h| is_empty(&tiles, h)));
+                at = match next.choose(rng) {
+                    Some(&h) => h,
+                    None => break,
+                };
+            }
+        }
+
+        // Terrain
+        for _ in 0..self.patches {
+            let &space = PATCH_SPACES.choose(rng)?;
+            let mut at = *open_tiles(&tiles).choose(rng)?;
+            for _ in 0..self.cluster_size {
+                if let Some(tile) = tiles.get_mut(&at) { tile.space = space; }
+                let next = sorted(at.neighbors().filter(|h| is_empty(&tiles, h)));
+                at = match next.choose(rng) {
+                    Some(&h) => h,
+                    None => break,
+                };
+            }
+        }
+
+        // Chokepoints
+        for _ in 0..self.chokepoints {
+            let &from = rim.choose(rng)?;
+            let to = Hex { x: -from.x, y: -from.y };
+            let line: Vec<Hex> = from.line_to(to).collect();
+            let gap = rng.gen_range(line.len() / 4, 3 * line.len() / 4 + 1);
+            for (ix, &hex) in line.iter().enumerate() {
+                if ix != gap { wall(&mut tiles, hex); }
+            }
+        }
+
+        // Connectivity: keep the biggest connected region and wall off the
+        // rest.  Each pass takes out a whole region.
+        let probe = Map::from_tiles(tiles.clone());
+        let mut left = open_tiles(&tiles);
+        let mut biggest: Vec<Hex> = vec![];
+        while let Some(&start) = left.first() {
+            let reach = probe.distances(vec![start], |_, tile| tile.walk_cost());
+            let (region, rest): (Vec<Hex>, Vec<Hex>) = left.into_iter()
+                .partition(|&h| reach.get(h).is_some());
+            if region.len() > biggest.len() { biggest = region; }
+            left = rest;
+        }
+        if biggest.len() < self.min_open { return None; }
+        let keep: HashSet<Hex> = biggest.into_iter().collect();
+        for hex in open_tiles(&tiles) {
+            if !keep.contains(&hex) { wall(&mut tiles, hex); }
+        }
+        Some(Map::from_tiles(tiles))
+    }
+}
+
+/// Picks start hexes for the player and one NPC per footprint.  Every hex of
+/// each NPC is at least `min_distance` from the player, and none start next to
+/// each other.  Only plain floor is used.
+pub fn spawns<R: Rng>(map: &Map, rng: &mut R, footprints: &[Footprint], min_distance: i32) -> Result<(Hex, Vec<Hex>)> {
+    let tiles = map.tiles();
+    let open: Vec<Hex> = open_tiles(tiles).into_iter()
+        .filter(|h| is_empty(tiles, h))
+        .collect();
+    let mut players = open.clone();
+    players.shuffle(rng);
+    for player in players {
+        let mut far: Vec<Hex> = open.iter().cloned()
+            .filter(|h| h.distance_to(player) >= min_distance)
+            .collect();
+        far.shuffle(rng);
+        let mut npcs: Vec<Hex> = vec![];
+        let mut taken: Vec<Hex> = vec![];
+        for hex in far {
+            let footprint = some_or!(footprints.get(npcs.len()), break);
+            let covers = footprint.at(hex);
+            let fits = covers.iter().all(|h| {
+                is_empty(tiles, h)
+                    && h.distance_to(player) >= min_distance
+                    && taken.iter().all(|t| t.distance_to(*h) > 1)
+            });
+            if fits {
+                npcs.push(hex);
+                taken.extend(covers);
+            }
+        }
+        if npcs.len() == footprints.len() { return Ok((player, npcs)); }
+    }
+    Err(Error::Obstructed)
+}
+
+// Tiles are kept in a hash map, so anything drawing on the rng has to work
+// from a sorted list to stay deterministic.
+fn sorted<I: Iterator<Item=Hex>>(hexes: I) -> Vec<Hex> {
+    let mut out: Vec<Hex> = hexes.collect();
+    out.sort_by_key(|h| (h.y, h.x));
+    out
+}
+
+fn open_tiles(tiles: &HashMap<Hex, Tile>) -> Vec<Hex> {
+    sorted(tiles.iter().filter(|(_, t)| t.is_open()).map(|(h, _)| *h))
+}
+
+fn is_empty(tiles: &HashMap<Hex, Tile>, hex: &Hex) -> bool {
+    tiles.get(hex).map_or(false, |t| t.space == Space::Empty)
+}
+
+fn wall(tiles: &mut HashMap<Hex, Tile>, hex: Hex) {
+    if let Some(tile) = tiles.get_mut(&hex) {
+        tile.space = Space::Wall;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rng::WorldRng;
+
+    fn gen() -> MapGen {
+        MapGen {
+            radius: 8, bites: 4, clusters: 4, cluster_size: 5, chokepoints: 2, patches: 3,
+            min_open: 40,
+        }
+    }
+
+    #[test]
+    fn generated_maps_are_connected() {
+        for seed in 0..20 {
+            let map = gen().generate(&mut WorldRng::new(seed)).unwrap();
+            let open = open_tiles(map.tiles());
+            let reach = map.distances(vec![open[0]], |_, t| t.walk_cost());
+            for hex in open {
+                assert!(reach.get(hex).is_some(), "seed {}: {:?} unreachable", seed, hex);
+            }
+        }
+    }
+
+    #[test]
+    fn spawns_keep_their_distance() {
+        for seed in 0..20 {
+            let mut rng = WorldRng::new(seed);
+            let map = gen().generate(&mut rng).unwrap();
+            let footprints = vec![Footprint(vec![(0, 0), (1, 0)]), Footprint::single(), Footprint::single()];
+            let (player, npcs) = spawns(&map, &mut rng, &footprints, 4).unwrap();
+            for (npc, footprint) in npcs.into_iter().zip(&footprints) {
+                for hex in footprint.at(npc) {
+                    assert!(hex.distance_to(player) >= 4, "seed {}", seed);
+                }
+            }
+        }
+    }
+}
@@ -1,17 +1,22 @@
+use serde::{Deserialize, Serialize};
+
 use crate::{
     id_map::{Id, IdMap},
 };
 
-#[derive(Debug, Clone)]
-pub struct ModStack<T> {
-    mods: IdMap<Mod<T>>,
-    mod_order: Vec<Id<Mod<T>>>,
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ModStack<M> {
+    mods: IdMap<M>,
+    mod_order: Vec<Id<M>>,
 }
 
-#[derive(Clone)]
-pub struct Mod<T>(pub fn(&mut T));
+/// A modification to a value of type `T`.  These are plain data rather than
+/// closures so that a `ModStack` can be saved and restored.
+pub trait Mod<T> {
+    fn apply(&self, value: &mut T);
+}
 
-impl<T> ModStack<T> {
+impl<M> ModStack<M> {
     pub fn new() -> Self {
         ModStack {
             mods: IdMap::new(),
@@ -19,39 +24,27 @@ impl<T> ModStack<T> {
         }
     }
 
-    pub fn eval(&self, base: T) -> T {
+    pub fn eval<T>(&self, base: T) -> T where M: Mod<T> {
         let mut value = base;
         for id in &self.mod_order {
             let m = self.mods.get(*id).unwrap();
-            m.0(&mut value);
+            m.apply(&mut value);
         }
         value
     }
 
-    pub fn mods(&self) -> &IdMap<Mod<T>> { &self.mods }
+    pub fn mods(&self) -> &IdMap<M> { &self.mods }
 
-    pub fn add(&mut self, m: Mod<T>) -> Id<Mod<T>> {
+    pub fn add(&mut self, m: M) -> Id<M> {
         let id = self.mods.add(m);
         self.mod_order.push(id);
         id
     }
 
-    pub fn remove(&mut self, id: Id<Mod<T>>) -> bool {
+    pub fn remove(&mut self, id: Id<M>) -> bool {
         if self.mods.remove(id).is_none() { return false; }
         let ix = self.mod_order.iter().position(|i| *i == id).unwrap();
         self.mod_order.remove(ix);
         true
     }
 }
-
-impl<T> std::fmt::Debug for Mod<T> {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        f.debug_tuple("Mod")
-            .field(&(self.0 as * const ()))
-            .finish()
-    }
-}
-
-impl<T> Default for Mod<T> {
-    fn default() -> Self { Mod(|_| { }) }
-}
\ No newline at end of file
@@ -3,25 +3,32 @@ use std::{
     iter::FromIterator,
 };
 
-use serde::{Serialize};
+use hex::Hex;
+use serde::{Deserialize, Serialize};
 use ts_data_derive::TsData;
-use wasm_bindgen::prelude::*;
 
 use crate::{
     action::{Action, Event, Path, Tag, action, event, to_creature},
     creature::{Creature},
     error::{Error, Result},
     id_map::Id,
+    map::Map,
     part::{Part, PartTag},
+    registry::Saved,
     serde_empty,
+    some_or,
     world::World,
     world_ext::WorldExt,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct NPC {
     pub intent: Intent,
     pub behavior: Box<dyn Behavior>,
+    // Where the player was when this NPC last saw them; NPCs don't know
+    // where the player is otherwise.
+    #[serde(default)]
+    pub last_seen: Option<Hex>,
 }
 
 impl NPC {
@@ -43,7 +50,7 @@ impl NPC {
     }
 }
 
-#[derive(Debug, Clone, Serialize, TsData)]
+#[derive(Debug, Clone, Serialize, Deserialize, TsData)]
 pub struct Intent {
     pub name: String,
     pub from: Option<Id<Part>>,
@@ -53,6 +60,15 @@ pub struct Intent {
 
 impl Intent {
     pub fn move_(&self, world: &mut World, source: Id<Creature>) -> Result<Vec<Event>> {
+        // The behavior gets first say in where to go.
+        let behavior = world.creatures().get(source)
+            .and_then(|c| c.npc.as_ref())
+            .map(|n| n.behavior.clone());
+        if let Some(to) = behavior.and_then(|b| b.destination(world, source, self)) {
+            let &from = world.map().creatures().get(&source).ok_or(Error::NoSuchCreature)?;
+            if to == from { return Ok(vec![]); }
+            return Ok(world.move_creature(source, to));
+        }
         self.kind.move_(world, source)
     }
 
@@ -90,9 +106,14 @@ impl Intent {
     }
 }
 
-#[derive(Debug, Clone, Serialize, TsData)]
+#[derive(Debug, Clone, Serialize, Deserialize, TsData)]
 pub enum IntentKind {
-    Attack { damage: i32, range: Range },
+    Attack {
+        damage: i32,
+        range: Range,
+        #[serde(default)]
+        movement: Movement,
+    },
     #[serde(with = "serde_empty")]
     Stunned,
 }
@@ -101,11 +122,12 @@ impl IntentKind {
     fn check(&self, world: &World, source: Id<Creature>) -> Result<()> {
         match self {
             IntentKind::Attack { range, .. } => {
+                if !world.sees(source, world.player_id()) {
+                    return Err(Error::OutOfRange);
+                }
                 let creature_pos = world.map().creatures().get(&source).ok_or(Error::OutOfBounds)?;
-                let player_pos = world.map().creatures().get(&world.player_id()).ok_or(Error::OutOfBounds)?;
-                let dist = creature_pos.distance_to(*player_pos);
-                match range {
-                    Range::Melee => if dist != 1 { return Err(Error::OutOfRange); }
+                if !range.reaches(world.map(), source, *creature_pos, world.player_id()) {
+                    return Err(Error::OutOfRange);
                 }
                 Ok(())
             }
@@ -115,14 +137,17 @@ impl IntentKind {
 
     fn move_(&self, world: &mut World, source: Id<Creature>) -> Result<Vec<Event>> {
         match self {
+            IntentKind::Attack { .. } if !world.sees(source, world.player_id()) => search(world, source),
             IntentKind::Attack { range: Range::Melee, .. } => move_to_melee(world, source),
+            IntentKind::Attack { range, movement, .. } => move_to_range(world, source, range, *movement),
             IntentKind::Stunned => Ok(vec![]),
         }
     }
 
-    fn act(&self, world: &mut World, source: Id<Creature>, _part: Option<Id<Part>>) -> Vec<Event> {
+    fn act(&self, world: &mut World, source: Id<Creature>, part: Option<Id<Part>>) -> Vec<Event> {
         match self {
             IntentKind::Attack { damage, .. } => {
+                let damage = powered(world, source, part, *damage);
                 let player_id = world.player_id();
                 let pid = {
                     let player = world.creatures().get(player_id).unwrap();
@@ -136,7 +161,7 @@ impl IntentKind {
                     source: Path::Creature { cid: source },
                     target: Path::Part { cid: player_id, pid },
                     tags: HashSet::from_iter(vec![Tag::Attack]),
-                    data: action::Hit { damage: *damage },
+                    data: action::Hit { damage },
                 })
             }
             IntentKind::Stunned => vec![to_creature(source, event::FloatText { text: "Stunned!".into() })]
@@ -144,14 +169,67 @@ impl IntentKind {
     }
 }
 
-#[derive(Debug, Clone, Serialize, TsData)]
+/// `base` scaled by the power of the part `source` attacks with, if any.
+pub fn powered(world: &World, source: Id<Creature>, part: Option<Id<Part>>, base: i32) -> i32 {
+    part.and_then(|pid| world.creatures().get(source)?.parts.get(pid))
+        .map_or(base, |p| p.scale(base))
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, TsData)]
 pub enum Range {
+    #[serde(with = "serde_empty")]
     Melee,
-    // TASK: Ranged
+    Ranged { min: i32, max: i32, needs_los: bool },
 }
 
-pub trait Behavior: BehaviorClone + std::fmt::Debug + Send {
+impl Range {
+    /// Could a creature `source` standing at `from` hit creature `target`
+    /// from there?  Distance is between the nearest hexes each covers.
+    pub fn reaches(&self, map: &Map, source: Id<Creature>, from: Hex, target: Id<Creature>) -> bool {
+        let dist = some_or!(map.gap(source, from, target), return false);
+        match *self {
+            Range::Melee => dist == 1,
+            Range::Ranged { min, max, needs_los } => {
+                if dist < min || dist > max { return false; }
+                if !needs_los { return true; }
+                let los = map.los_from(from, source);
+                map.occupied(target).iter().any(|h| los.contains(h))
+            }
+        }
+    }
+
+    // How far `from` is from being in range, for picking a hex to approach.
+    fn shortfall(&self, map: &Map, source: Id<Creature>, from: Hex, target: Id<Creature>) -> i32 {
+        if self.reaches(map, source, from, target) { return 0; }
+        let dist = map.gap(source, from, target).unwrap_or(std::i32::MAX / 2);
+        let (min, max) = match *self {
+            Range::Melee => (1, 1),
+            Range::Ranged { min, max, .. } => (min, max),
+        };
+        if dist < min { min - dist }
+        else if dist > max { dist - max }
+        else { 1 }  // In distance but not in sight.
+    }
+}
+
+/// How an NPC positions itself for an attack during its move.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, TsData)]
+pub enum Movement {
+    /// Move as little as possible to get in range.
+    Approach,
+    /// Get in range, as far from the target as range allows.
+    KeepDistance,
+}
+
+impl Default for Movement {
+    fn default() -> Self { Movement::Approach }
+}
+
+pub trait Behavior: BehaviorClone + BehaviorSave + std::fmt::Debug + Send {
     fn intent(&mut self, world: &World, id: Id<Creature>) -> Vec<Intent>;
+    /// Where to move before carrying out `intent`; `None` leaves it to the
+    /// intent's own movement rules.
+    fn destination(&self, _world: &World, _id: Id<Creature>, _intent: &Intent) -> Option<Hex> { None }
 }
 
 pub trait BehaviorClone {
@@ -170,17 +248,236 @@ impl Clone for Box<dyn Behavior> {
     fn clone(&self) -> Self { self.clone_box() }
 }
 
+pub trait BehaviorSave {
+    fn save_key(&self) -> &'static str;
+    fn save(&self) -> serde_json::Result<serde_json::Value>;
+}
+
+impl<T> BehaviorSave for T
+where T: 'static + Behavior + Saved,
+{
+    fn save_key(&self) -> &'static str { T::KEY }
+    fn save(&self) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(self)
+    }
+}
+
 fn move_to_melee(world: &mut World, id: Id<Creature>) -> Result<Vec<Event>> {
     let map = world.map();
-    let player_hex = map.creatures().get(&world.player_id())
+    let player_id = world.player_id();
+    let &from = map.creatures().get(&id)
         .ok_or(Error::NoSuchCreature)?;
-    let from = map.creatures().get(&id)
+    if map.gap(id, from, player_id).ok_or(Error::NoSuchCreature)? <= 1 { return Ok(vec![]); }
+    let costs = map.distances(vec![from], |h, _| map.footprint_cost(id, h));
+    let best = costs.iter()
+        .filter(|&(h, _)| map.gap(id, h, player_id) == Some(1))
+        .min_by_key(|&(h, c)| (c, h.y, h.x))
+        .ok_or(Error::Obstructed)?;
+    Ok(world.move_creature(id, best.0))
+}
+
+// Heads for where the player was last seen, if anywhere.
+fn search(world: &mut World, id: Id<Creature>) -> Result<Vec<Event>> {
+    let creature = world.creatures().get(id).ok_or(Error::NoSuchCreature)?;
+    let target = match creature.npc.as_ref().and_then(|n| n.last_seen) {
+        Some(h) => h,
+        None => return Ok(vec![]),
+    };
+    let map = world.map();
+    let &from = map.creatures().get(&id).ok_or(Error::NoSuchCreature)?;
+    let costs = map.distances(vec![from], |h, _| map.footprint_cost(id, h));
+    let best = std::iter::once(target).chain(target.neighbors())
+        .filter_map(|h| costs.get(h).map(|c| (h, c)))
+        .min_by_key(|&(h, c)| (h.distance_to(target), c, h.y, h.x))
+        .ok_or(Error::Obstructed)?;
+    if best.0 == from { return Ok(vec![]); }
+    Ok(world.move_creature(id, best.0))
+}
+
+fn move_to_range(world: &mut World, id: Id<Creature>, range: &Range, movement: Movement) -> Result<Vec<Event>> {
+    let map = world.map();
+    let target = world.player_id();
+    let &from = map.creatures().get(&id)
         .ok_or(Error::NoSuchCreature)?;
-    if from.distance_to(*player_hex) <= 1 { return Ok(vec![]); }
-    let mut near: Vec<_> = player_hex.neighbors()
-        .filter(|h| map.tiles().get(h).map_or(false, |t| t.is_open()))
-        .collect();
-    if near.is_empty() { return Err(Error::Obstructed); }
-    near.sort_by(|a, b| from.distance_to(*a).cmp(&from.distance_to(*b)));
-    Ok(world.move_creature(id, near[0]))
-}
\ No newline at end of file
+    if movement == Movement::Approach && range.reaches(map, id, from, target) {
+        return Ok(vec![]);
+    }
+    let mp = world.creatures().get(id).ok_or(Error::NoSuchCreature)?.cur_mp;
+    let keep_away = movement == Movement::KeepDistance;
+    // Prefer hexes in range, then (if keeping distance) far from the target,
+    // then cheap to reach from where we are now; the hex itself breaks ties so
+    // the choice doesn't depend on map order.
+    let costs = map.distances(vec![from], |h, _| map.footprint_cost(id, h));
+    let best = costs.iter()
+        .filter(|&(_, c)| c <= mp)
+        .min_by_key(|&(h, c)| (
+            range.shortfall(map, id, h, target),
+            if keep_away { -map.gap(id, h, target).unwrap_or(0) } else { 0 },
+            c,
+            (h.y, h.x),
+        ))
+        .map(|(h, _)| h)
+        .ok_or(Error::Obstructed)?;
+    if best == from { return Ok(vec![]); }
+    Ok(world.move_creature(id, best))
+}
+
+/// A behavior that tries every intent it has from every hex it can reach,
+/// on a copy of the world, and goes with whatever scores best.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Planner {
+    pub intents: Vec<Intent>,
+    pub utility: Utility,
+    // The best hex for each intent, by name, from the last plan.  Only good
+    // while we and the player are where we both were then.
+    #[serde(default)]
+    plans: Vec<(String, Hex)>,
+    #[serde(default)]
+    planned_at: Option<(Hex, Hex)>,
+}
+
+/// Weights for scoring a simulated outcome.  Higher scores are better.
+///
+/// Only our own move and action are simulated, not the player's reply, so
+/// "damage avoided" is just what we take along the way plus a flat penalty
+/// for ending up in the player's sight.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Utility {
+    /// Per point of damage dealt to the player.
+    pub damage_dealt: i32,
+    /// Extra per point of damage dealt to the player's vital parts.
+    pub vital_focus: i32,
+    /// Per point of damage taken along the way, from terrain and the like.
+    pub damage_taken: i32,
+    /// For ending the turn where the player can see us.
+    pub exposed: i32,
+    /// Per hex between us and the player at the end.
+    pub distance: i32,
+}
+
+impl Default for Utility {
+    fn default() -> Self {
+        Utility { damage_dealt: 10, vital_focus: 5, damage_taken: 10, exposed: 0, distance: 1 }
+    }
+}
+
+impl Utility {
+    fn score(&self, before: &World, after: &World, id: Id<Creature>) -> i32 {
+        let player_id = before.player_id();
+        let mut dealt = 0;
+        let mut vital = 0;
+        for (lost, is_vital) in hp_lost(before, after, player_id) {
+            dealt += lost;
+            if is_vital { vital += lost; }
+        }
+        let taken: i32 = hp_lost(before, after, id).iter().map(|(lost, _)| lost).sum();
+        let map = after.map();
+        let gap = map.creatures().get(&id)
+            .and_then(|&at| map.gap(id, at, player_id))
+            .unwrap_or(0);
+        let exposed = if after.sees(player_id, id) { 1 } else { 0 };
+        self.damage_dealt * dealt
+            + self.vital_focus * vital
+            - self.damage_taken * taken
+            - self.exposed * exposed
+            - self.distance * gap
+    }
+}
+
+// HP each part of `cid` lost between `before` and `after`, and whether it's vital.
+fn hp_lost(before: &World, after: &World, cid: Id<Creature>) -> Vec<(i32, bool)> {
+    let (old, new) = match (before.creatures().get(cid), after.creatures().get(cid)) {
+        (Some(o), Some(n)) => (o, n),
+        _ => return vec![],
+    };
+    old.parts.iter()
+        .map(|(&pid, part)| {
+            let hp = new.parts.get(pid).map_or(0, |p| p.cur_hp);
+            (part.cur_hp - hp, part.tags().contains(&PartTag::Vital))
+        })
+        .collect()
+}
+
+impl Planner {
+    pub fn new(intents: Vec<Intent>, utility: Utility) -> Self {
+        Planner { intents, utility, plans: vec![], planned_at: None }
+    }
+
+    // Where we and the player are now.
+    fn positions(world: &World, id: Id<Creature>) -> Option<(Hex, Hex)> {
+        let creatures = world.map().creatures();
+        Some((*creatures.get(&id)?, *creatures.get(&world.player_id())?))
+    }
+
+    // Every hex we could move to this turn, in a fixed order.
+    fn reachable(world: &World, id: Id<Creature>) -> Vec<Hex> {
+        let map = world.map();
+        let (from, mp) = match (map.creatures().get(&id), world.creatures().get(id)) {
+            (Some(&h), Some(c)) => (h, c.cur_mp),
+            _ => return vec![],
+        };
+        let costs = map.distances(vec![from], |h, _| map.footprint_cost(id, h));
+        let mut out: Vec<Hex> = costs.iter()
+            .filter(|&(_, c)| c <= mp)
+            .map(|(h, _)| h)
+            .collect();
+        out.sort_by_key(|h| (h.y, h.x));
+        out
+    }
+
+    // The best score `intent` can get from any of `dests`, and that hex.
+    fn plan(&self, world: &World, id: Id<Creature>, intent: &Intent, dests: &[Hex]) -> Option<(i32, Hex)> {
+        let &from = world.map().creatures().get(&id)?;
+        let mut best: Option<(i32, Hex)> = None;
+        for &to in dests {
+            let mut tmp = world.scratch();
+            if to != from {
+                let events = tmp.move_creature(id, to);
+                if Event::is_failure(&events) { continue; }
+            }
+            // Failing to act still leaves the move worth scoring.
+            let _ = intent.act(&mut tmp, id);
+            let score = self.utility.score(world, &tmp, id);
+            if best.map_or(true, |(b, _)| score > b) {
+                best = Some((score, to));
+            }
+        }
+        best
+    }
+}
+
+impl Behavior for Planner {
+    fn intent(&mut self, world: &World, id: Id<Creature>) -> Vec<Intent> {
+        self.plans.clear();
+        self.planned_at = None;
+        // Nothing to plan around; the intents go in the order they're listed.
+        if !world.sees(id, world.player_id()) { return self.intents.clone(); }
+        let dests = Planner::reachable(world, id);
+        let mut scored: Vec<(i32, Intent)> = vec![];
+        for intent in &self.intents {
+            let plan = self.plan(world, id, intent, &dests);
+            if let Some((_, hex)) = plan {
+                self.plans.push((intent.name.clone(), hex));
+            }
+            scored.push((plan.map_or(std::i32::MIN, |(s, _)| s), intent.clone()));
+        }
+        self.planned_at = Planner::positions(world, id);
+        // Stable, so ties go to whichever was listed first.
+        scored.sort_by_key(|(s, _)| -(*s as i64));
+        scored.into_iter().map(|(_, i)| i).collect()
+    }
+
+    fn destination(&self, world: &World, id: Id<Creature>, intent: &Intent) -> Option<Hex> {
+        // Nothing to plan around; go looking instead.
+        if !world.sees(id, world.player_id()) { return None; }
+        if self.planned_at.is_some() && self.planned_at == Planner::positions(world, id) {
+            if let Some((_, hex)) = self.plans.iter().find(|(name, _)| *name == intent.name) {
+                return Some(*hex);
+            }
+        }
+        let dests = Planner::reachable(world, id);
+        self.plan(world, id, intent, &dests).map(|(_, h)| h)
+    }
+}
+
+impl Saved for Planner { const KEY: &'static str = "planner"; }
@@ -3,9 +3,8 @@ use std::{
     iter::FromIterator,
 };
 
-use serde::{Serialize};
+use serde::{Deserialize, Serialize};
 use ts_data_derive::TsData;
-use wasm_bindgen::prelude::wasm_bindgen;
 
 use crate::{
     action::{action, event, Action, Event},
@@ -16,13 +15,13 @@ use crate::{
     mod_stack::{Mod, ModStack},
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Part {
     // Structure
     pub name: String,
     pub cards: IdMap<Card>,
     pub base_tags: HashSet<PartTag>,
-    pub tag_mods: ModStack<HashSet<PartTag>>,
+    pub tag_mods: ModStack<TagMod>,
     pub entity: Entity,
     // Stats
     pub max_hp: i32,
@@ -30,13 +29,20 @@ pub struct Part {
     pub thought: i32, // action points
     pub memory: i32,  // hand size
     pub mp: i32,
-    /* TASK: remaining part attributes
-    power: i32,  // TASK: level?
-    capacity: i32,
-    joints: Vec<Joint>,
-    */
+    // Scales the effects of this part's cards; see `Part::scale`.
+    #[serde(default)]
+    pub power: i32,
+    // How many cards this part can hold; see `Part::add_card`.
+    #[serde(default = "default_capacity")]
+    pub capacity: i32,
+    // Parts attached to this one; see `Creature::detach`.
+    pub joints: Vec<Joint>,
 }
 
+const DEFAULT_CAPACITY: i32 = 3;
+
+fn default_capacity() -> i32 { DEFAULT_CAPACITY }
+
 impl Part {
     pub fn new<S: Into<String>>(name: S, tags: &[PartTag], max_hp: i32) -> Self {
         Part {
@@ -46,6 +52,8 @@ impl Part {
             tag_mods: ModStack::new(),
             entity: Entity::new(),
             thought: 0, memory: 0, mp: 0,
+            power: 0, capacity: DEFAULT_CAPACITY,
+            joints: vec![],
             max_hp, cur_hp: max_hp,
         }
     }
@@ -54,12 +62,30 @@ impl Part {
         self.tag_mods.eval(self.base_tags.clone())
     }
 
+    /// Scale a card's base `amount` by this part's power; each point adds a
+    /// quarter (or takes one away, if negative).  The change is rounded
+    /// toward zero, so a 2-point card at power -1 still does 2, and the
+    /// result never goes below zero.
+    pub fn scale(&self, amount: i32) -> i32 {
+        std::cmp::max(0, amount + amount * self.power / 4)
+    }
+
+    /// Fails if the part is already at capacity.
+    pub fn add_card(&mut self, card: Card) -> Result<Id<Card>> {
+        if self.cards.len() as i32 >= self.capacity {
+            return Err(Error::NotEnough("capacity".into()));
+        }
+        Ok(self.cards.add(card))
+    }
+
     pub fn resolve(&mut self, action: &Action) -> Result<Vec<Event>> {
         let old_tags = self.tags();
         let mut out = self.resolve_(action)?;
         let new_tags = self.tags();
-        let added: Vec<_> = new_tags.difference(&old_tags).cloned().collect();
-        let removed: Vec<_> = old_tags.difference(&new_tags).cloned().collect();
+        let mut added: Vec<_> = new_tags.difference(&old_tags).cloned().collect();
+        added.sort();
+        let mut removed: Vec<_> = old_tags.difference(&new_tags).cloned().collect();
+        removed.sort();
         if !added.is_empty() {
             out.push(action.carry(event::TagsSet { tags: added }));
         }
@@ -118,10 +144,24 @@ impl Part {
     }
 }
 
-pub type TagMod = Mod<HashSet<PartTag>>;
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum TagMod {
+    Add(PartTag),
+    Remove(PartTag),
+}
+
+impl Mod<HashSet<PartTag>> for TagMod {
+    fn apply(&self, tags: &mut HashSet<PartTag>) {
+        match self {
+            TagMod::Add(tag) => { tags.insert(*tag); }
+            TagMod::Remove(tag) => { tags.remove(tag); }
+        }
+    }
+}
+
 pub type TagModId = Id<TagMod>;
 
-#[derive(Debug, Copy, Clone, PartialEq, Eq, Hash, Serialize, TsData)]
+#[derive(Debug, Copy, Clone, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize, TsData)]
 pub enum PartTag {
     // State
     Vital, Broken, Open,
@@ -133,10 +173,23 @@ pub enum PartTag {
     Arm, Leg,
 }
 
-/* TASK
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize, TsData)]
 pub struct Joint {
-    required: HashSet<PartTag>,
-    attached: Option<Id<Part>>,
+    // A part needs all of these to attach here.
+    pub required: HashSet<PartTag>,
+    pub attached: Option<Id<Part>>,
 }
-*/
\ No newline at end of file
+
+impl Joint {
+    pub fn new(required: &[PartTag], attached: Option<Id<Part>>) -> Self {
+        Joint {
+            required: HashSet::from_iter(required.iter().cloned()),
+            attached,
+        }
+    }
+
+    pub fn accepts(&self, part: &Part) -> bool {
+        let tags = part.tags();
+        self.required.iter().all(|t| tags.contains(t))
+    }
+}
\ No newline at end of file
@@ -0,0 +1,141 @@
+use std::collections::HashMap;
+
+use lazy_static::lazy_static;
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+    de::{DeserializeOwned, Error as _},
+    ser::Error as _,
+};
+
+use crate::{
+    card::Card,
+    error::{Error, Result},
+    library,
+    npc,
+    status::Status,
+};
+
+/// Types that can be saved by the registry.  `KEY` is the stable id written
+/// to save files; changing it will break existing saves.
+pub trait Saved: Serialize + DeserializeOwned {
+    const KEY: &'static str;
+}
+
+type Load<T> = fn(serde_json::Value) -> serde_json::Result<T>;
+
+/// Maps stable string ids to the things that can't be serialized directly:
+/// card constructors, status types and NPC behaviors.
+pub struct Registry {
+    cards: HashMap<&'static str, fn() -> Card>,
+    statuses: HashMap<&'static str, Load<Box<dyn Status>>>,
+    behaviors: HashMap<&'static str, Load<Box<dyn npc::Behavior>>>,
+}
+
+lazy_static! {
+    static ref REGISTRY: Registry = library::registry();
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Registry {
+            cards: HashMap::new(),
+            statuses: HashMap::new(),
+            behaviors: HashMap::new(),
+        }
+    }
+
+    pub fn global() -> &'static Registry { &REGISTRY }
+
+    // Accessors
+
+    pub fn card(&self, key: &str) -> Result<Card> {
+        let make = self.cards.get(key).ok_or_else(|| Error::Unregistered(key.into()))?;
+        Ok(make())
+    }
+
+    pub fn status(&self, key: &str, data: serde_json::Value) -> Result<Box<dyn Status>> {
+        let load = self.statuses.get(key).ok_or_else(|| Error::Unregistered(key.into()))?;
+        load(data).map_err(|e| Error::InvalidSave(e.to_string()))
+    }
+
+    pub fn behavior(&self, key: &str, data: serde_json::Value) -> Result<Box<dyn npc::Behavior>> {
+        let load = self.behaviors.get(key).ok_or_else(|| Error::Unregistered(key.into()))?;
+        load(data).map_err(|e| Error::InvalidSave(e.to_string()))
+    }
+
+    // Mutators
+
+    pub fn add_card(&mut self, make: fn() -> Card) {
+        let key = make().key;
+        if self.cards.insert(key, make).is_some() {
+            panic!("duplicate card key {:?}", key);
+        }
+    }
+
+    pub fn add_status<T: 'static + Status + Saved>(&mut self) {
+        if self.statuses.insert(T::KEY, load_status::<T>).is_some() {
+            panic!("duplicate status key {:?}", T::KEY);
+        }
+    }
+
+    pub fn add_behavior<T: 'static + npc::Behavior + Saved>(&mut self) {
+        if self.behaviors.insert(T::KEY, load_behavior::<T>).is_some() {
+            panic!("duplicate behavior key {:?}", T::KEY);
+        }
+    }
+}
+
+fn load_status<T: 'static + Status + Saved>(data: serde_json::Value) -> serde_json::Result<Box<dyn Status>> {
+    Ok(Box::new(serde_json::from_value::<T>(data)?))
+}
+
+fn load_behavior<T: 'static + npc::Behavior + Saved>(data: serde_json::Value) -> serde_json::Result<Box<dyn npc::Behavior>> {
+    Ok(Box::new(serde_json::from_value::<T>(data)?))
+}
+
+#[derive(Serialize, Deserialize)]
+struct SavedObject {
+    key: String,
+    data: serde_json::Value,
+}
+
+impl Serialize for Box<dyn Status> {
+    fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        let data = self.save().map_err(S::Error::custom)?;
+        SavedObject { key: self.save_key().into(), data }.serialize(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Box<dyn Status> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        let saved = SavedObject::deserialize(d)?;
+        Registry::global().status(&saved.key, saved.data).map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for Box<dyn npc::Behavior> {
+    fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        let data = self.save().map_err(S::Error::custom)?;
+        SavedObject { key: self.save_key().into(), data }.serialize(s)
+    }
+}
+
+impl<'de> Deserialize<'de> for Box<dyn npc::Behavior> {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        let saved = SavedObject::deserialize(d)?;
+        Registry::global().behavior(&saved.key, saved.data).map_err(D::Error::custom)
+    }
+}
+
+impl Serialize for Card {
+    fn serialize<S: Serializer>(&self, s: S) -> std::result::Result<S::Ok, S::Error> {
+        s.serialize_str(self.key)
+    }
+}
+
+impl<'de> Deserialize<'de> for Card {
+    fn deserialize<D: Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        let key = String::deserialize(d)?;
+        Registry::global().card(&key).map_err(D::Error::custom)
+    }
+}
@@ -0,0 +1,95 @@
+use std::hash::Hasher;
+
+use fnv::FnvHasher;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    action::{Event, Path, Tag},
+    command::Command,
+    creature::Creature,
+    error::{Error, Result},
+    world::World,
+};
+
+/// Version of the replay file format itself; the rules a replay is checked
+/// against are whatever the current build implements.
+pub const VERSION: u32 = 4;
+
+/// The seed, starting encounter and player inputs for a game, plus a checksum
+/// of the events they produced.  Re-running a replay and comparing checksums
+/// detects any change in rules behavior; `tests/replay.rs` does this for the
+/// recorded games in `tests/replays`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Replay {
+    pub version: u32,
+    pub seed: u64,
+    pub encounter: String,
+    pub entrant: Option<Creature>,
+    pub commands: Vec<Command>,
+    pub checksum: u64,
+}
+
+impl Replay {
+    pub fn new(world: &World) -> Self {
+        Replay {
+            version: VERSION,
+            seed: world.seed(),
+            encounter: world.encounter().into(),
+            entrant: world.entrant().cloned(),
+            commands: world.log().to_vec(),
+            checksum: world.checksum(),
+        }
+    }
+
+    pub fn parse(data: &str) -> Result<Replay> {
+        let replay: Replay = serde_json::from_str(data)
+            .map_err(|e| Error::InvalidSave(e.to_string()))?;
+        if replay.version != VERSION {
+            return Err(Error::InvalidSave(format!("unsupported replay version {}", replay.version)));
+        }
+        Ok(replay)
+    }
+
+    pub fn to_json(&self) -> Result<String> {
+        serde_json::to_string(self).map_err(|e| Error::InvalidSave(e.to_string()))
+    }
+
+    /// Re-executes the replay from scratch, returning the final world if the
+    /// resulting events match the recorded checksum.
+    pub fn run(&self) -> Result<World> {
+        let mut world = World::from_encounter(self.seed, self.entrant.clone(), &self.encounter)?;
+        for command in &self.commands {
+            world.apply(command.clone());
+        }
+        if world.checksum() != self.checksum {
+            return Err(Error::ReplayMismatch { expected: self.checksum, actual: world.checksum() });
+        }
+        Ok(world)
+    }
+}
+
+/// Folds `events` into a running checksum.  Tags are sorted first so the
+/// result doesn't depend on hash set iteration order.
+pub fn checksum(prev: u64, events: &[Event]) -> u64 {
+    #[derive(Serialize)]
+    struct Canonical<'a, T> {
+        source: &'a Path,
+        target: &'a Path,
+        tags: Vec<Tag>,
+        data: &'a T,
+    }
+    let mut hasher = FnvHasher::with_key(prev);
+    for event in events {
+        let mut tags: Vec<Tag> = event.tags.iter().cloned().collect();
+        tags.sort();
+        let canonical = Canonical {
+            source: &event.source,
+            target: &event.target,
+            tags,
+            data: &event.data,
+        };
+        let json = serde_json::to_string(&canonical).unwrap();
+        hasher.write(json.as_bytes());
+    }
+    hasher.finish()
+}
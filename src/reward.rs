@@ -0,0 +1,71 @@
+use serde::{Deserialize, Serialize};
+use ts_data_derive::TsData;
+
+use crate::{
+    creature::Creature,
+    id_map::Id,
+    part::{Part, PartTag},
+    world::{GameState, World},
+};
+
+/// A part the player can take from a defeated enemy, and where it could go.
+#[derive(Debug, Clone, Serialize, TsData)]
+pub struct Reward {
+    pub cid: Id<Creature>,
+    pub pid: Id<Part>,
+    pub sockets: Vec<Socket>,
+}
+
+/// A joint on one of the player's parts.
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, TsData)]
+pub struct Socket {
+    pub pid: Id<Part>,
+    pub joint: usize,
+    // The part currently on the joint, which grafting will discard.
+    pub replaces: Option<Id<Part>>,
+}
+
+impl World {
+    /// The player gets to take one part after winning a fight.
+    pub fn rewards(&self) -> Vec<Reward> {
+        if self.state() != GameState::Won || self.harvested() { return vec![]; }
+        let player = self.creatures().get(self.player_id()).unwrap();
+        let mut out = vec![];
+        for (&cid, creature) in self.creatures() {
+            if cid == self.player_id() || !creature.dead { continue; }
+            for (&pid, part) in &creature.parts {
+                if part.tags().contains(&PartTag::Broken) { continue; }
+                let sockets = sockets_for(player, part);
+                if !sockets.is_empty() {
+                    out.push(Reward { cid, pid, sockets });
+                }
+            }
+        }
+        out
+    }
+
+    /// Is grafting `pid` from `cid` onto joint `joint` of the player's
+    /// `parent` one of the `rewards` on offer?
+    pub fn offers(&self, cid: Id<Creature>, pid: Id<Part>, parent: Id<Part>, joint: usize) -> bool {
+        self.rewards().iter().any(|r|
+            r.cid == cid && r.pid == pid
+            && r.sockets.iter().any(|s| s.pid == parent && s.joint == joint))
+    }
+}
+
+// Joints `part` fits on, either free or holding a non-vital part.
+fn sockets_for(player: &Creature, part: &Part) -> Vec<Socket> {
+    let mut out = vec![];
+    for (&pid, parent) in &player.parts {
+        if parent.tags().contains(&PartTag::Broken) { continue; }
+        for (joint, j) in parent.joints.iter().enumerate() {
+            if !j.accepts(part) { continue; }
+            let vital = j.attached
+                .and_then(|id| player.parts.get(id))
+                .map_or(false, |p| p.tags().contains(&PartTag::Vital));
+            if vital { continue; }
+            out.push(Socket { pid, joint, replaces: j.attached });
+        }
+    }
+    out
+}
@@ -0,0 +1,40 @@
+use rand::{Error, RngCore, SeedableRng};
+use rand_pcg::Pcg32;
+use serde::{Deserialize, Serialize};
+
+/// All randomness in the game goes through a single seeded generator owned by
+/// the `World`, so that the same seed and inputs produce the same events.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldRng {
+    inner: Pcg32,
+    draws: u64,
+}
+
+impl WorldRng {
+    pub fn new(seed: u64) -> Self {
+        WorldRng { inner: Pcg32::seed_from_u64(seed), draws: 0 }
+    }
+
+    /// How many times the generator has been drawn from; if this changes
+    /// across an update, that update revealed random information.
+    pub fn draws(&self) -> u64 { self.draws }
+}
+
+impl RngCore for WorldRng {
+    fn next_u32(&mut self) -> u32 {
+        self.draws += 1;
+        self.inner.next_u32()
+    }
+    fn next_u64(&mut self) -> u64 {
+        self.draws += 1;
+        self.inner.next_u64()
+    }
+    fn fill_bytes(&mut self, dest: &mut [u8]) {
+        self.draws += 1;
+        self.inner.fill_bytes(dest)
+    }
+    fn try_fill_bytes(&mut self, dest: &mut [u8]) -> Result<(), Error> {
+        self.draws += 1;
+        self.inner.try_fill_bytes(dest)
+    }
+}
@@ -0,0 +1,95 @@
+use serde::{Deserialize, Serialize};
+use ts_data_derive::TsData;
+
+use crate::{
+    creature::Creature,
+    error::{Error, Result},
+    library,
+    map_file::Layout,
+    map_gen::MapGen,
+    world::{GameState, World},
+};
+
+/// The enemies of a single fight and the arena to fight them in.  See
+/// `library::encounter`.
+pub enum Encounter {
+    Generated {
+        enemies: Vec<Creature>,
+        map: MapGen,
+        // How close to the player an enemy can start.
+        min_distance: i32,
+    },
+    // Everything comes from the layout's spawns; see `map_file`.
+    Authored { layout: Layout },
+}
+
+/// A sequence of encounters fought by the same player, who keeps their
+/// damage, parts and deck from one fight to the next.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Run {
+    seed: u64,
+    stage: usize,
+    // The player as they left the last fight, or `None` before the first.
+    player: Option<Creature>,
+    state: RunState,
+}
+
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Serialize, Deserialize, TsData)]
+pub enum RunState {
+    InProgress,
+    Won,
+    Lost,
+}
+
+impl Run {
+    pub fn new(seed: u64) -> Self {
+        Run { seed, stage: 0, player: None, state: RunState::InProgress }
+    }
+
+    // Accessors
+
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn stage(&self) -> usize { self.stage }
+    pub fn stage_count(&self) -> usize { library::encounter::RUN.len() }
+    pub fn player(&self) -> Option<&Creature> { self.player.as_ref() }
+    pub fn state(&self) -> RunState { self.state }
+
+    /// Builds the world for the current encounter.
+    pub fn start(&self) -> Result<World> {
+        if self.state != RunState::InProgress { return Err(Error::GameOver); }
+        let key = library::encounter::RUN[self.stage];
+        World::from_encounter(self.encounter_seed(), self.player.clone(), key)
+    }
+
+    // Mutators
+
+    /// Records the result of the current encounter, which must be over.  A
+    /// win carries the player, rewards and all, on to the next encounter.
+    pub fn finish(&mut self, world: &World) -> Result<RunState> {
+        if self.state != RunState::InProgress { return Err(Error::GameOver); }
+        match world.state() {
+            GameState::Play => return Err(Error::InvalidAction),
+            GameState::Lost => self.state = RunState::Lost,
+            GameState::Won => {
+                self.player = Some(world.player().clone());
+                self.stage += 1;
+                if self.stage >= self.stage_count() {
+                    self.state = RunState::Won;
+                }
+            }
+        }
+        Ok(self.state)
+    }
+
+    // Private
+
+    // Each encounter gets its own seed so that replaying one doesn't depend
+    // on the ones before it.  The stage is mixed in (splitmix64's finalizer)
+    // rather than added, so neighbouring runs don't share fights.
+    fn encounter_seed(&self) -> u64 {
+        let mut z = self.seed ^ (self.stage as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+}
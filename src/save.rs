@@ -0,0 +1,69 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    error::{Error, Result},
+    run::Run,
+    world::World,
+};
+
+/// Bump this whenever the serialized form of `World` or `Run` changes incompatibly,
+/// and add a migration to `World::load` if old saves should keep working.
+pub const VERSION: u32 = 6;
+
+#[derive(Serialize)]
+struct SaveOut<'a> {
+    version: u32,
+    world: &'a World,
+}
+
+#[derive(Deserialize)]
+struct SaveIn {
+    version: u32,
+    world: serde_json::Value,
+}
+
+impl World {
+    pub fn save(&self) -> Result<String> {
+        serde_json::to_string(&SaveOut { version: VERSION, world: self })
+            .map_err(|e| Error::InvalidSave(e.to_string()))
+    }
+
+    pub fn load(data: &str) -> Result<World> {
+        let save: SaveIn = serde_json::from_str(data)
+            .map_err(|e| Error::InvalidSave(e.to_string()))?;
+        match save.version {
+            VERSION => serde_json::from_value(save.world)
+                .map_err(|e| Error::InvalidSave(e.to_string())),
+            v => Err(Error::InvalidSave(format!("unsupported version {}", v))),
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct RunOut<'a> {
+    version: u32,
+    run: &'a Run,
+}
+
+#[derive(Deserialize)]
+struct RunIn {
+    version: u32,
+    run: serde_json::Value,
+}
+
+impl Run {
+    pub fn save(&self) -> Result<String> {
+        serde_json::to_string(&RunOut { version: VERSION, run: self })
+            .map_err(|e| Error::InvalidSave(e.to_string()))
+    }
+
+    pub fn load(data: &str) -> Result<Run> {
+        let save: RunIn = serde_json::from_str(data)
+            .map_err(|e| Error::InvalidSave(e.to_string()))?;
+        match save.version {
+            VERSION => serde_json::from_value(save.run)
+                .map_err(|e| Error::InvalidSave(e.to_string())),
+            v => Err(Error::InvalidSave(format!("unsupported version {}", v))),
+        }
+    }
+}
@@ -0,0 +1,22 @@
+use std::{
+    collections::HashMap,
+    hash::Hash,
+};
+
+use serde::{
+    Deserialize, Deserializer, Serialize, Serializer,
+};
+
+// For maps with keys that aren't valid JSON object keys; stored as a list of
+// [key, value] pairs instead.
+pub fn serialize<K, V, S>(map: &HashMap<K, V>, s: S) -> Result<S::Ok, S::Error>
+    where K: Serialize, V: Serialize, S: Serializer
+{
+    s.collect_seq(map.iter())
+}
+pub fn deserialize<'de, K, V, D>(d: D) -> Result<HashMap<K, V>, D::Error>
+    where K: Deserialize<'de> + Eq + Hash, V: Deserialize<'de>, D: Deserializer<'de>
+{
+    let pairs: Vec<(K, V)> = Vec::deserialize(d)?;
+    Ok(pairs.into_iter().collect())
+}
@@ -1,9 +1,10 @@
 use crate::{
     action::{Action, Event, Path},
     id_map::Id,
+    registry::Saved,
 };
 
-pub trait Status: StatusClone + std::fmt::Debug {
+pub trait Status: StatusClone + StatusSave + std::fmt::Debug {
     fn name(&self) -> &'static str;
     fn alter_order(&self) -> AlterOrder { AlterOrder::Misc }
     fn trigger_order(&self) -> TriggerOrder { TriggerOrder::Misc }
@@ -48,4 +49,19 @@ impl Clone for Box<dyn Status> {
     fn clone(&self) -> Self {
         self.clone_box()
     }
-}
\ No newline at end of file
+}
+
+pub trait StatusSave {
+    fn save_key(&self) -> &'static str;
+    fn save(&self) -> serde_json::Result<serde_json::Value>;
+}
+
+impl<T> StatusSave for T
+where
+    T: 'static + Status + Saved,
+{
+    fn save_key(&self) -> &'static str { T::KEY }
+    fn save(&self) -> serde_json::Result<serde_json::Value> {
+        serde_json::to_value(self)
+    }
+}
@@ -8,7 +8,7 @@ use crate::{
     card, creature,
     id_map::Id,
     npc,
-    part::{self, PartTag},
+    part::{self, Joint, PartTag},
     wasm::{
         to_js_value,
         card::Card,
@@ -69,7 +69,10 @@ pub struct Part {
     maxHp: i32,
     curHp: i32,
     thought: i32,
+    power: i32,
+    capacity: i32,
     tags: Vec<PartTag>,
+    joints: Vec<Joint>,
     entity: Entity,
 }
 
@@ -87,9 +90,12 @@ impl Part {
             id, creatureId, cards,
             name: source.name.clone(),
             thought: source.thought,
+            power: source.power,
+            capacity: source.capacity,
             maxHp: source.max_hp,
             curHp: source.cur_hp,
             tags: source.tags().into_iter().collect(),
+            joints: source.joints.clone(),
             entity: Entity::new(&source.entity),
         }
     }
@@ -14,6 +14,9 @@ use crate::{
 pub struct InPlay {
     #[wasm_bindgen(skip)]
     pub wrapped: card::InPlay,
+    // Targets picked so far; all methods below are about the next pick.
+    #[wasm_bindgen(skip)]
+    pub picked: Vec<action::Path>,
 }
 
 #[allow(non_snake_case)]
@@ -21,19 +24,30 @@ pub struct InPlay {
 impl InPlay {
     #[wasm_bindgen(skip_typescript)]
     pub fn range(&self, world: &World) -> Array /* Hex[] */ {
-        self.wrapped.behavior.range(&self.wrapped.source(), &world.wrapped).iter()
+        self.wrapped.behavior.range(&self.wrapped.source(), &world.wrapped, &self.picked).iter()
             .map(to_js_value)
             .collect()
     }
     #[wasm_bindgen(skip_typescript)]
     pub fn targetValid(&self, world: &World, target: JsValue) -> bool {
-        self.wrapped.behavior.target_valid(&world.wrapped, &self.wrapped.source(), &from_js_value::<action::Path>(target))
+        let target: action::Path = from_js_value(target);
+        self.wrapped.behavior.target_valid(&world.wrapped, &self.wrapped.source(), &self.picked, &target)
     }
     #[wasm_bindgen(skip_typescript)]
     pub fn preview(&self, world: &World, target: JsValue) -> Array /* Event[] */ {
-        let source = action::Path::Part { cid: self.wrapped.creature_id, pid: self.wrapped.part_id };
+        let targets = self.with_target(target);
+        if targets.len() < self.wrapped.behavior.target_count() {
+            return Array::new();
+        }
+        self.wrapped.behavior.preview(&world.wrapped, self.wrapped.source(), &targets).iter()
+            .map(to_js_value)
+            .collect()
+    }
+    #[wasm_bindgen(skip_typescript)]
+    pub fn targetArea(&self, world: &World, target: JsValue) -> Array /* Hex[] */ {
         let target: action::Path = from_js_value(target);
-        self.wrapped.behavior.preview(&world.wrapped, source, target).iter()
+        let spec = self.wrapped.behavior.target_spec(&self.picked);
+        spec.area(&world.wrapped, &self.wrapped.source(), &target).iter()
             .map(to_js_value)
             .collect()
     }
@@ -41,7 +55,42 @@ impl InPlay {
     pub fn apCost(&self) -> i32 { self.wrapped.ap_cost }
     #[wasm_bindgen(skip_typescript)]
     pub fn getTargetSpec(&self) -> JsValue {
-        to_js_value(&self.wrapped.behavior.target_spec())
+        to_js_value(&self.wrapped.behavior.target_spec(&self.picked))
+    }
+    /// Number of targets still to be picked, including the next one.
+    #[wasm_bindgen(getter)]
+    pub fn remaining(&self) -> usize {
+        self.wrapped.behavior.target_count().saturating_sub(self.picked.len())
+    }
+    #[wasm_bindgen(skip_typescript)]
+    pub fn getPicked(&self) -> Array /* Path[] */ {
+        self.picked.iter().map(to_js_value).collect()
+    }
+    #[wasm_bindgen(skip_typescript)]
+    pub fn pick(&mut self, world: &World, target: JsValue) -> bool {
+        let target: action::Path = from_js_value(target);
+        if !self.wrapped.behavior.target_valid(&world.wrapped, &self.wrapped.source(), &self.picked, &target) {
+            return false;
+        }
+        self.picked.push(target);
+        true
+    }
+    #[wasm_bindgen(skip_typescript)]
+    pub fn unpick(&mut self) -> bool {
+        self.picked.pop().is_some()
+    }
+}
+
+impl InPlay {
+    pub fn new(wrapped: card::InPlay) -> Self {
+        InPlay { wrapped, picked: vec![] }
+    }
+
+    /// The picked targets plus a final one.
+    pub fn with_target(&self, target: JsValue) -> Vec<action::Path> {
+        let mut targets = self.picked.clone();
+        targets.push(from_js_value(target));
+        targets
     }
 }
 
@@ -51,6 +100,10 @@ interface InPlay {
     range(world: World): Hex[];
     targetValid(world: World, target: Path): boolean;
     preview(world: World, target: Path): Event[];
+    targetArea(world: World, target: Path): Hex[];
     getTargetSpec(): TargetSpec;
+    getPicked(): Path[];
+    pick(world: World, target: Path): boolean;
+    unpick(): boolean;
 }
-"#;
\ No newline at end of file
+"#;
@@ -8,6 +8,7 @@ mod creature;
 mod entity;
 mod extern_ts;
 mod in_play;
+mod run;
 mod world;
 
 fn to_js_value<T: Serialize>(t: &T) -> JsValue { to_value(t).unwrap() }
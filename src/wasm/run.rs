@@ -0,0 +1,70 @@
+use wasm_bindgen::prelude::*;
+
+use crate::{
+    run,
+    wasm::{
+        world::World,
+        to_js_value,
+    },
+};
+
+#[wasm_bindgen]
+#[derive(Debug)]
+pub struct Run {
+    #[wasm_bindgen(skip)]
+    pub wrapped: run::Run,
+}
+
+#[allow(non_snake_case)]
+#[wasm_bindgen]
+impl Run {
+    #[wasm_bindgen(constructor)]
+    pub fn new(seed: u32) -> Self {
+        Run { wrapped: run::Run::new(seed as u64) }
+    }
+
+    pub fn load(data: &str) -> Result<Run, JsValue> {
+        let wrapped = run::Run::load(data)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(Run { wrapped })
+    }
+
+    pub fn save(&self) -> Result<String, JsValue> {
+        self.wrapped.save().map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    // Accessors
+
+    #[wasm_bindgen(getter)]
+    pub fn stage(&self) -> usize { self.wrapped.stage() }
+
+    #[wasm_bindgen(getter)]
+    pub fn stageCount(&self) -> usize { self.wrapped.stage_count() }
+
+    #[wasm_bindgen(skip_typescript)]
+    pub fn state(&self) -> JsValue /* RunState */ {
+        to_js_value(&self.wrapped.state())
+    }
+
+    pub fn startEncounter(&self) -> Result<World, JsValue> {
+        let world = self.wrapped.start()
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(World::from_world(world))
+    }
+
+    // Updates
+
+    pub fn finishEncounter(&self, world: &World) -> Result<Run, JsValue> {
+        let mut wrapped = self.wrapped.clone();
+        wrapped.finish(&world.wrapped)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(Run { wrapped })
+    }
+}
+
+#[wasm_bindgen(typescript_custom_section)]
+const RUN_TS: &'static str = r#"
+interface Run {
+    state(): RunState;
+}
+"#;
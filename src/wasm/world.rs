@@ -1,3 +1,5 @@
+use std::collections::HashSet;
+
 use hex::Hex;
 use js_sys::Array;
 use wasm_bindgen::{
@@ -8,14 +10,19 @@ use wasm_bindgen::{
 use crate::{
     action::{Action, Event, Path},
     card,
+    command::Command,
     creature,
+    history::History,
+    replay::Replay,
+    reward::Reward,
     id_map::Id,
-    map::{Space, Tile},
+    map::Tile,
     npc,
-    part::{PartTag},
+    part::{self, PartTag},
     wasm::{
         card::Card,
         creature::Creature,
+        entity::Entity,
         in_play::InPlay,
         from_js_value, to_js_value,
     },
@@ -29,16 +36,39 @@ use crate::{
 pub struct World {
     #[wasm_bindgen(skip)]
     pub wrapped: world::World,
+    #[wasm_bindgen(skip)]
+    pub history: History,
 }
 
 #[allow(non_snake_case)]
 #[wasm_bindgen]
 impl World {
     #[wasm_bindgen(constructor)]
-    pub fn new() -> Self {
-        World {
-            wrapped: world::World::new()
-        }
+    pub fn new(seed: u32) -> Self {
+        World::from_world(world::World::new(seed as u64))
+    }
+
+    pub fn load(data: &str) -> Result<World, JsValue> {
+        let wrapped = world::World::load(data)
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(World::from_world(wrapped))
+    }
+
+    pub fn save(&self) -> Result<String, JsValue> {
+        self.wrapped.save().map_err(|e| JsValue::from_str(&e.to_string()))
+    }
+
+    #[wasm_bindgen(js_name = fromReplay)]
+    pub fn from_replay(data: &str) -> Result<World, JsValue> {
+        let wrapped = Replay::parse(data)
+            .and_then(|r| r.run())
+            .map_err(|e| JsValue::from_str(&e.to_string()))?;
+        Ok(World::from_world(wrapped))
+    }
+
+    pub fn replay(&self) -> Result<String, JsValue> {
+        Replay::new(&self.wrapped).to_json()
+            .map_err(|e| JsValue::from_str(&e.to_string()))
     }
 
     // Accessors
@@ -50,18 +80,23 @@ impl World {
 
     #[wasm_bindgen(skip_typescript)]
     pub fn getTile(&self, hex: JsValue) -> JsValue /* Tile | undefined */ {
-        self.wrapped.map().tiles()
-            .get(&from_js_value::<Hex>(hex))
-            .map_or(JsValue::undefined(), |t| to_js_value(&t))
+        let hex: Hex = from_js_value(hex);
+        if !self.wrapped.known().contains(&hex) { return JsValue::undefined(); }
+        let visible = self.wrapped.visible_to(self.wrapped.player_id());
+        self.wrapped.map().tiles().get(&hex)
+            .map_or(JsValue::undefined(), |t| to_js_value(&shown_tile(&visible, hex, t)))
     }
 
     #[wasm_bindgen(skip_typescript)]
     pub fn getTiles(&self) -> Array /* [Hex, Tile][] */ {
+        let known = self.wrapped.known();
+        let visible = self.wrapped.visible_to(self.wrapped.player_id());
         self.wrapped.map().tiles().iter()
+            .filter(|(h, _)| known.contains(h))
             .map(|(h, t)| {
                 let tuple = Array::new();
                 tuple.push(&to_js_value::<Hex>(h));
-                tuple.push(&to_js_value::<Tile>(t));
+                tuple.push(&to_js_value::<Tile>(&shown_tile(&visible, *h, t)));
                 tuple
             })
             .collect()
@@ -70,20 +105,25 @@ impl World {
     #[wasm_bindgen(skip_typescript)]
     pub fn getCreature(&self, id: JsValue) -> JsValue {
         let id: Id<creature::Creature> = from_js_value(id);
+        if !self.visible_creatures().contains(&id) { return JsValue::undefined(); }
         self.wrapped.creatures().get(id)
             .map_or(JsValue::undefined(), |c| Creature::new(id, c).js())
     }
 
     #[wasm_bindgen(skip_typescript)]
     pub fn getCreatures(&self) -> Array {
+        let visible = self.visible_creatures();
         self.wrapped.creatures().iter()
+            .filter(|(id, _)| visible.contains(id))
             .map(|(id, c)| Creature::new(*id, c).js())
             .collect()
     }
 
     #[wasm_bindgen(skip_typescript)]
     pub fn getCreatureMap(&self) -> Array /* [Id<Creature>, Hex][] */ {
+        let visible = self.visible_creatures();
         self.wrapped.map().creatures().iter()
+            .filter(|(id, _)| visible.contains(id))
             .map(|(id, hex)| {
                 let tuple = Array::new();
                 tuple.push(&to_js_value::<Id<creature::Creature>>(id));
@@ -93,13 +133,44 @@ impl World {
             .collect()
     }
 
+    #[wasm_bindgen(skip_typescript)]
+    pub fn getVisible(&self) -> Array /* Hex[] */ {
+        self.wrapped.visible_to(self.wrapped.player_id()).iter()
+            .map(to_js_value::<Hex>)
+            .collect()
+    }
+
+    #[wasm_bindgen(skip_typescript)]
+    pub fn getHexEntities(&self) -> Array /* [Hex, Entity][] */ {
+        let known = self.wrapped.known();
+        self.wrapped.hex_entities().into_iter()
+            .filter(|(hex, _)| known.contains(hex))
+            .map(|(hex, entity)| {
+                let tuple = Array::new();
+                tuple.push(&to_js_value::<Hex>(&hex));
+                tuple.push(&to_js_value::<Entity>(&Entity::new(entity)));
+                tuple
+            })
+            .collect()
+    }
+
     #[wasm_bindgen(skip_typescript)]
     pub fn getCreatureHex(&self, id: JsValue) -> JsValue /* Hex | undefined */ {
         let id: Id<creature::Creature> = from_js_value(id);
+        if !self.visible_creatures().contains(&id) { return JsValue::undefined(); }
         self.wrapped.map().creatures().get(&id)
             .map_or(JsValue::undefined(), to_js_value::<Hex>)
     }
 
+    #[wasm_bindgen(skip_typescript)]
+    pub fn getOccupied(&self, id: JsValue) -> Array /* Hex[] */ {
+        let id: Id<creature::Creature> = from_js_value(id);
+        if !self.visible_creatures().contains(&id) { return Array::new(); }
+        self.wrapped.map().occupied(id).iter()
+            .map(to_js_value::<Hex>)
+            .collect()
+    }
+
     #[wasm_bindgen(skip_typescript)]
     pub fn getCreatureRange(&self, id: JsValue) -> Array /* Hex[] */ {
         let id: Id<creature::Creature> = from_js_value(id);
@@ -107,12 +178,8 @@ impl World {
             Some(c) => c.cur_mp,
             None => return Array::new(),
         };
-        let start = match self.wrapped.map().creatures().get(&id) {
-            Some(h) => h,
-            None => return Array::new(),
-        };
         let space_only = id != self.wrapped.player_id();
-        self.wrapped.map().range_from(*start, range, space_only).into_iter()
+        self.wrapped.map().range_from(id, range, space_only).into_iter()
             .map(|hex| to_js_value::<Hex>(&hex))
             .collect()
     }
@@ -140,7 +207,7 @@ impl World {
         let creature_id: Id<creature::Creature> = from_js_value(creature_id);
         let hand_ix: usize = from_js_value(hand_ix);
         card::Card::start_play(&self.wrapped, creature_id, hand_ix).ok()
-            .map(|ip| InPlay { wrapped: ip })
+            .map(InPlay::new)
     }
 
     #[wasm_bindgen(skip_typescript)]
@@ -162,7 +229,7 @@ impl World {
         let to: Hex = from_js_value(to);
         let mut new = self.wrapped.clone();
         new.tracer = None;
-        new.move_creature(new.player_id(), to).iter()
+        new.apply(Command::MovePlayer { to }).iter()
             .map(to_js_value).collect()
     }
 
@@ -173,7 +240,7 @@ impl World {
         let los = self.wrapped.map().los_from(hex, id);
         self.wrapped.map().tiles().iter()
             .filter_map(|(h, t)|
-                if t.space == Space::Empty && !los.contains(h) { Some(h) }
+                if !t.space.blocks_sight() && !los.contains(h) { Some(h) }
                 else { None }
             )
             .map(to_js_value)
@@ -189,6 +256,12 @@ impl World {
         }
     }
 
+    #[wasm_bindgen(skip_typescript)]
+    pub fn canUndo(&self) -> bool { self.history.can_undo() }
+
+    #[wasm_bindgen(skip_typescript)]
+    pub fn canRedo(&self) -> bool { self.history.can_redo() }
+
     #[wasm_bindgen(skip_typescript)]
     pub fn cardUI(&self, js_card: JsValue, target: JsValue) -> JsValue {
         let js_card: Card = from_js_value(js_card);
@@ -198,29 +271,52 @@ impl World {
         JsValue::from_serde(&ui).unwrap()
     }
 
+    #[wasm_bindgen(skip_typescript)]
+    pub fn getRewards(&self) -> Array /* Reward[] */ {
+        self.wrapped.rewards().iter()
+            .map(to_js_value::<Reward>)
+            .collect()
+    }
+
     // Updates
 
     #[wasm_bindgen(skip_typescript)]
     pub fn finishPlay(&self, in_play: InPlay, target: JsValue) -> Array /* [World, Event[]] */ {
-        let target: Path = from_js_value(target);
-        let mut newWorld = self.wrapped.clone();
-        let events = in_play.wrapped.finish(&mut newWorld, &target);
-        world_update(newWorld, &events)
+        let targets = in_play.with_target(target);
+        self.world_update(Command::PlayCard { hand_ix: in_play.wrapped.hand_ix, targets })
     }
 
     #[wasm_bindgen(skip_typescript)]
     pub fn npcTurn(&self) -> Array /* [World, Event[]] */ {
-        let mut newWorld = self.wrapped.clone();
-        let events = newWorld.npc_turn();
-        world_update(newWorld, &events)
+        self.world_update(Command::EndTurn)
     }
 
     #[wasm_bindgen(skip_typescript)]
     pub fn movePlayer(&self, to: JsValue) -> Array /* [World, Event[]] */ {
         let to: Hex = from_js_value(to);
-        let mut new = self.wrapped.clone();
-        let events = new.move_creature(new.player_id(), to);
-        world_update(new, &events)
+        self.world_update(Command::MovePlayer { to })
+    }
+
+    #[wasm_bindgen(skip_typescript)]
+    pub fn harvest(&self, cid: JsValue, pid: JsValue, parent: JsValue, joint: usize) -> Array /* [World, Event[]] */ {
+        let cid: Id<creature::Creature> = from_js_value(cid);
+        let pid: Id<part::Part> = from_js_value(pid);
+        let parent: Id<part::Part> = from_js_value(parent);
+        self.world_update(Command::Harvest { cid, pid, parent, joint })
+    }
+
+    #[wasm_bindgen(skip_typescript)]
+    pub fn undo(&self) -> Option<World> {
+        let mut history = self.history.clone();
+        let wrapped = history.undo()?.clone();
+        Some(World { wrapped, history })
+    }
+
+    #[wasm_bindgen(skip_typescript)]
+    pub fn redo(&self) -> Option<World> {
+        let mut history = self.history.clone();
+        let wrapped = history.redo()?.clone();
+        Some(World { wrapped, history })
     }
 
     // Debugging
@@ -236,6 +332,34 @@ impl World {
 }
 
 impl World {
+    pub fn from_world(wrapped: world::World) -> Self {
+        World {
+            history: History::new(wrapped.clone()),
+            wrapped,
+        }
+    }
+
+    fn world_update(&self, command: Command) -> Array {
+        let mut new = self.wrapped.clone();
+        let events = new.apply(command.clone());
+        let mut history = self.history.clone();
+        if !Event::is_failure(&events) {
+            history.commit(command, new.clone());
+        }
+        let out = Array::new();
+        out.push(&JsValue::from(World { wrapped: new, history }));
+        out.push(&JsValue::from(events.iter().map(to_js_value).collect::<Array>()));
+        out
+    }
+
+    // The player and whatever creatures they can see.
+    fn visible_creatures(&self) -> HashSet<Id<creature::Creature>> {
+        let player_id = self.wrapped.player_id();
+        self.wrapped.creatures().keys().cloned()
+            .filter(|&id| id == player_id || self.wrapped.sees(player_id, id))
+            .collect()
+    }
+
     fn scaled_intent(&self, cid: Id<creature::Creature>) -> Option<npc::Intent> {
         let creature = self.wrapped.creatures().get(cid)?;
         let npc = creature.npc.as_ref()?;
@@ -249,7 +373,8 @@ impl World {
                 };
                 let target = Path::Part { cid: self.wrapped.player_id(), pid: Id::invalid() };
                 let scopes = vec![world::Scope::SourcePart, world::Scope::SourceCreature, world::Scope::World];
-                match self.wrapped.scale_damage(&source, &target, *damage, scopes).0 {
+                let base = npc::powered(&self.wrapped, cid, npc.intent.from, *damage);
+                match self.wrapped.scale_damage(&source, &target, base, scopes).0 {
                     Some(new_damage) => {
                         *damage = new_damage;
                         Some(intent)
@@ -261,6 +386,14 @@ impl World {
     }
 }
 
+// What the player can tell about a tile: nobody's shown standing on it unless
+// it's in sight right now.
+fn shown_tile(visible: &HashSet<Hex>, hex: Hex, tile: &Tile) -> Tile {
+    let mut tile = tile.clone();
+    if !visible.contains(&hex) { tile.creature = None; }
+    tile
+}
+
 #[wasm_bindgen(typescript_custom_section)]
 const WORLD_TS: &'static str = r#"
 interface World {
@@ -272,7 +405,10 @@ interface World {
     getCreature(id: Id<Creature>): Creature | undefined;
     getCreatures(): Creature[];
     getCreatureMap(): [Id<Creature>, Hex][];
+    getVisible(): Hex[];
+    getHexEntities(): [Hex, Entity][];
     getCreatureHex(id: Id<Creature>): Hex | undefined;
+    getOccupied(id: Id<Creature>): Hex[];
     getCreatureRange(id: Id<Creature>): Hex[];
     isPlayable(card: Card): boolean;
     startPlay(creatureId: Id<Creature>, handIx: number): InPlay | undefined;
@@ -281,13 +417,19 @@ interface World {
     simulateMove(to: Hex): Event[];
     shadeFrom(hex: Hex, id: Id<Creature>): Hex[];
     scaledIntent(cid: Id<Creature>): Intent | undefined;
+    canUndo(): boolean;
+    canRedo(): boolean;
     cardUI(card: Card, target: Path): any;
+    getRewards(): Reward[];
 
     // Updates
 
     finishPlay(inPlay: InPlay, target: Path): [World, Event[]];
     npcTurn(): [World, Event[]];
     movePlayer(to: Hex): [World, Event[]];
+    harvest(cid: Id<Creature>, pid: Id<Part>, parent: Id<Part>, joint: number): [World, Event[]];
+    undo(): World | undefined;
+    redo(): World | undefined;
 
     // Debugging
 
@@ -295,13 +437,6 @@ interface World {
 }
 "#;
 
-fn world_update(new: world::World, events: &[Event]) -> Array {
-    let out = Array::new();
-    out.push(&JsValue::from(World { wrapped: new }));
-    out.push(&JsValue::from(events.iter().map(to_js_value).collect::<Array>()));
-    out
-}
-
 #[wasm_bindgen]
 extern "C" {
     pub type Tracer;
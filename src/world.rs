@@ -1,57 +1,152 @@
 use std::{
-    collections::HashSet,
+    collections::{HashMap, HashSet},
     iter::FromIterator,
 };
 
 use enum_iterator::IntoEnumIterator;
-use hex::{self, Hex};
-use serde::Serialize;
+use hex::Hex;
+use serde::{Deserialize, Serialize};
 use ts_data_derive::TsData;
-use wasm_bindgen::prelude::wasm_bindgen;
 
 use crate::{
     action::{
         Action, Event, EventData, Meta, Path, Tag,
         action, event, to_creature,
     },
+    command::Command,
     creature::{Creature},
     entity::{Entity},
     error::{Error, Result},
     id_map::{Id, IdMap},
     library,
-    map::{Map},
+    map::{Fov, Map},
+    map_file::Spawn,
+    map_gen,
+    run::Encounter,
+    part::Part,
+    replay,
+    rng::WorldRng,
+    serde_pairs,
     status::{StatusDone, StatusId},
     some_or,
 };
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct World {
     map: Map,
     player_id: Id<Creature>,
     creatures: IdMap<Creature>,
     entity: Entity,
+    // Statuses sitting on the ground; only hexes that have had one get an
+    // entry.
+    #[serde(default, with = "serde_pairs")]
+    hexes: HashMap<Hex, Entity>,
+    rng: WorldRng,
+    // What this world was built from; see `World::from_encounter`.
+    #[serde(default = "default_encounter")]
+    encounter: String,
+    #[serde(default)]
+    entrant: Option<Creature>,
+    // Whether the post-combat reward has been taken.
+    #[serde(default)]
+    harvested: bool,
+    // Every tile the player has seen this encounter.
+    #[serde(default)]
+    known: HashSet<Hex>,
+    // Replay recording
+    seed: u64,
+    log: Vec<Command>,
+    checksum: u64,
+    #[serde(skip)]
     pub tracer: Option<Box<dyn Tracer>>,
 }
 
 impl World {
-    pub fn new() -> Self {
+    pub fn new(seed: u64) -> Self {
+        World::from_encounter(seed, None, DEFAULT_ENCOUNTER)
+            .expect("the default encounter should always load")
+    }
+
+    /// A copy to try things out on, without the replay log or tracer.
+    pub fn scratch(&self) -> World {
+        World {
+            map: self.map.clone(),
+            player_id: self.player_id,
+            creatures: self.creatures.clone(),
+            entity: self.entity.clone(),
+            hexes: self.hexes.clone(),
+            rng: self.rng.clone(),
+            encounter: self.encounter.clone(),
+            entrant: self.entrant.clone(),
+            harvested: self.harvested,
+            known: self.known.clone(),
+            seed: self.seed,
+            log: vec![],
+            checksum: self.checksum,
+            tracer: None,
+        }
+    }
+
+    /// Sets up the encounter `key` from `library::encounter`.  `entrant` is
+    /// the player as they were at the end of the last fight, if any; with
+    /// `None` the player starts fresh.
+    pub fn from_encounter(seed: u64, entrant: Option<Creature>, key: &str) -> Result<Self> {
+        let encounter = library::encounter::get(key)?;
+        let mut rng = WorldRng::new(seed);
+        let (mut map, pc_start, enemies): (Map, Hex, Vec<(Creature, Hex)>) = match encounter {
+            Encounter::Generated { enemies, map, min_distance } => {
+                let map = map.generate(&mut rng)?;
+                let footprints: Vec<_> = enemies.iter().map(|e| e.footprint.clone()).collect();
+                let (pc_start, starts) = map_gen::spawns(&map, &mut rng, &footprints, min_distance)?;
+                (map, pc_start, enemies.into_iter().zip(starts).collect())
+            }
+            Encounter::Authored { layout } => {
+                let mut pc_start = None;
+                let mut enemies = vec![];
+                for (spawn, hex) in layout.spawns {
+                    match spawn {
+                        Spawn::Player => pc_start = Some(hex),
+                        _ => enemies.push((library::encounter::spawn(spawn), hex)),
+                    }
+                }
+                (layout.map, pc_start.ok_or(Error::OutOfBounds)?, enemies)
+            }
+        };
         let mut creatures = IdMap::new();
-        let mut map = Map::new();
-        let pc_id = creatures.add(library::player::player());
-        map.place_at(pc_id, hex::ORIGIN).unwrap();
-        let enemy_id = creatures.add(library::npc::Monopod::creature());
-        map.place_at(enemy_id, Hex { x: -4, y: 1 }).unwrap();
-        let enemy2_id = creatures.add(library::npc::Monopod::creature());
-        map.place_at(enemy2_id, Hex { x: 4, y: -1 }).unwrap();
+        let player = match &entrant {
+            Some(c) => c.rested(),
+            None => library::player::player(),
+        };
+        let footprint = player.footprint.clone();
+        let pc_id = creatures.add(player);
+        map.place(pc_id, pc_start, footprint)?;
+        for (enemy, hex) in enemies {
+            let footprint = enemy.footprint.clone();
+            let id = creatures.add(enemy);
+            map.place(id, hex, footprint)?;
+        }
         let mut out = World {
             map: map,
             player_id: pc_id,
             creatures: creatures,
             entity: Entity::new(),
+            hexes: HashMap::new(),
+            rng,
+            encounter: key.into(),
+            entrant,
+            harvested: false,
+            known: HashSet::new(),
+            seed,
+            log: vec![],
+            checksum: 0,
             tracer: None,
         };
+        for creature in out.creatures.values_mut() {
+            creature.reset_cards(&mut out.rng);
+        }
+        out.remember();
         out.execute(&to_creature(pc_id, action::NewHand));
-        out
+        Ok(out)
     }
 
     // Accessors
@@ -60,6 +155,37 @@ impl World {
     pub fn player_id(&self) -> Id<Creature> { self.player_id }
     pub fn creatures(&self) -> &IdMap<Creature> { &self.creatures }
     pub fn entity(&self) -> &Entity { &self.entity }
+    pub fn hex_entity(&self, hex: Hex) -> Option<&Entity> { self.hexes.get(&hex) }
+
+    /// Hexes with statuses on them, in a fixed order.
+    pub fn hex_entities(&self) -> Vec<(Hex, &Entity)> {
+        let mut out: Vec<_> = self.hexes.iter()
+            .filter(|(_, e)| !e.status.is_empty())
+            .map(|(h, e)| (*h, e))
+            .collect();
+        out.sort_by_key(|(h, _)| (h.y, h.x));
+        out
+    }
+    pub fn rng_draws(&self) -> u64 { self.rng.draws() }
+    pub fn seed(&self) -> u64 { self.seed }
+    pub fn log(&self) -> &[Command] { &self.log }
+    pub fn checksum(&self) -> u64 { self.checksum }
+    pub fn harvested(&self) -> bool { self.harvested }
+    pub fn encounter(&self) -> &str { &self.encounter }
+    pub fn entrant(&self) -> Option<&Creature> { self.entrant.as_ref() }
+    pub fn player(&self) -> &Creature { self.creatures.get(self.player_id).unwrap() }
+    pub fn known(&self) -> &HashSet<Hex> { &self.known }
+
+    /// The hexes creature `id` can currently see.
+    pub fn visible_to(&self, id: Id<Creature>) -> HashSet<Hex> {
+        self.map.los_of(id).unwrap_or_default()
+    }
+
+    /// Can `viewer` see creature `target` right now?
+    pub fn sees(&self, viewer: Id<Creature>, target: Id<Creature>) -> bool {
+        let visible = self.visible_to(viewer);
+        self.map.occupied(target).iter().any(|h| visible.contains(h))
+    }
 
     pub fn state(&self) -> GameState {
         let player = self.creatures.get(self.player_id).unwrap();
@@ -76,10 +202,57 @@ impl World {
 
     // Mutators
 
+    pub fn rng(&mut self) -> &mut WorldRng { &mut self.rng }
+
+    /// Switches how everyone's sight is worked out; see `map::Fov`.
+    pub fn set_fov(&mut self, fov: Fov) {
+        self.map.set_fov(fov);
+        self.remember();
+    }
+
+    /// Adds an applied command and its results to the replay log.
+    pub fn record(&mut self, command: Command, events: &[Event]) {
+        self.log.push(command);
+        self.checksum = replay::checksum(self.checksum, events);
+    }
+
     pub fn execute(&mut self, action: &Action) -> Vec<Event> {
         self.execute_(action, &HashSet::new())
     }
 
+    /// Takes part `pid` (and everything attached below it) from dead
+    /// creature `cid` and grafts it onto the player's `parent` at `joint`,
+    /// discarding whatever was there.  Only what `World::rewards` offers can
+    /// be taken.
+    pub fn harvest(&mut self, cid: Id<Creature>, pid: Id<Part>, parent: Id<Part>, joint: usize) -> Result<Vec<Event>> {
+        if !self.offers(cid, pid, parent, joint) { return Err(Error::InvalidTarget); }
+        let player_id = self.player_id;
+        // Work on copies so a failure leaves both creatures intact.
+        let mut player = self.creatures.get(player_id).ok_or(Error::NoSuchCreature)?.clone();
+        let mut from = self.creatures.get(cid).ok_or(Error::NoSuchCreature)?.clone();
+        let replaced = player.parts.get(parent).ok_or(Error::NoSuchPart)?
+            .joints.get(joint).ok_or(Error::InvalidTarget)?
+            .attached;
+        let mut out = vec![];
+        if let Some(old) = replaced {
+            for (old_id, _) in player.detach(old) {
+                out.push(part_event(player_id, old_id, event::Severed));
+            }
+        }
+        let tree = from.detach(pid);
+        for (old_id, _) in &tree {
+            out.push(part_event(cid, *old_id, event::Severed));
+        }
+        for new_id in player.attach_tree(parent, joint, tree)? {
+            let (new_parent, _) = player.parent_of(new_id).unwrap();
+            out.push(part_event(player_id, new_id, event::Attached { parent: new_parent }));
+        }
+        *self.creatures.get_mut(player_id).unwrap() = player;
+        *self.creatures.get_mut(cid).unwrap() = from;
+        self.harvested = true;
+        Ok(out)
+    }
+
     pub fn npc_turn(&mut self) -> Vec<Event> {
         let mut events = vec![];
 
@@ -104,10 +277,12 @@ impl World {
 
         for (id, intent) in npc_plays {
             // Motion
+            self.look(id);
             match intent.move_(self, id) {
                 Ok(es) => events.extend(es),
                 Err(e) => events.push(to_creature(id, event::FloatText { text: format!("{}!", e) })),
             }
+            self.look(id);
 
             // Action
             match intent.act(self, id) {
@@ -172,6 +347,7 @@ impl World {
                 let part = creature.parts.get_mut(*pid).ok_or(Error::NoSuchPart)?;
                 Ok(&mut part.entity)
             }
+            Path::Hex { hex } => self.hexes.get_mut(hex).ok_or(Error::InvalidTarget),
         }
     }
 
@@ -195,6 +371,31 @@ impl World {
                 scoped.insert(path.clone());
                 out.extend(self.apply_triggers_path(skip, event, &path));
             }
+            // Whatever's on the ground gets a look at anyone arriving.  Like
+            // terrain, each kind of status only goes off once for a big
+            // creature, and not for ground it was already standing on.
+            if let (EventData::Moved { from, to }, Some(cid)) = (&event.data, event.target.creature()) {
+                let footprint = self.map.footprint(cid);
+                let before = footprint.at(*from);
+                let mut entered: Vec<Hex> = footprint.at(*to).into_iter()
+                    .filter(|h| !before.contains(h))
+                    .collect();
+                entered.sort_by_key(|h| (h.y, h.x));
+                let mut fired = HashSet::new();
+                for hex in entered {
+                    let path = Path::Hex { hex };
+                    if !scoped.insert(path.clone()) { continue; }
+                    let mut hex_skip = skip.clone();
+                    if let Some(entity) = self.hexes.get(&hex) {
+                        for (&sid, status) in entity.status.iter() {
+                            if !fired.insert(status.name()) {
+                                hex_skip.insert((path.clone(), sid));
+                            }
+                        }
+                    }
+                    out.extend(self.apply_triggers_path(&hex_skip, event, &path));
+                }
+            }
             if event.is_global() {
                 for path in self.all_entity_paths() {
                     if scoped.contains(&path) { continue; }
@@ -240,6 +441,10 @@ impl World {
             action::Fail { description } => return simple(event::Failed { description: description.clone() }),
             // Entity
             action::AddStatus { status } => {
+                if let Path::Hex { hex } = &action.target {
+                    if !self.map.tiles().contains_key(hex) { return Err(Error::OutOfBounds); }
+                    self.hexes.entry(*hex).or_insert_with(Entity::new);
+                }
                 let entity = self.entity_mut(&action.target)?;
                 let id = entity.status.add(status.clone());
                 return simple(event::StatusAdded { id });
@@ -256,13 +461,46 @@ impl World {
             (Path::Creature { cid }, action::Move { to }) => {
                 let &from = self.map.creatures().get(&cid).ok_or(Error::NoSuchCreature)?;
                 self.map.move_to(*cid, *to)?;
+                self.remember();
                 return simple(event::Moved { from, to: *to });
             }
+            // Hex
+            // Hitting a hex hits whatever's there: an object if it can take
+            // it, otherwise whoever's standing on it.
+            (Path::Hex { hex }, action::Hit { damage }) => {
+                let tile = self.map.tiles().get(hex).ok_or(Error::OutOfBounds)?;
+                if tile.object.as_ref().and_then(|o| o.hp()).is_some() {
+                    let hp = self.map.hit_object(*hex, *damage)?;
+                    let mut out = vec![action.carry(event::ObjectHit { hp })];
+                    if hp <= 0 {
+                        // It may have been blocking the view.
+                        self.remember();
+                        out.push(action.carry(event::ObjectDestroyed));
+                    }
+                    return Ok(out);
+                }
+                let cid = some_or!(tile.creature, return simple(event::Nothing));
+                let pid = some_or!(self.first_open_part(cid), return simple(event::Nothing));
+                let mut hit = action.clone();
+                hit.target = Path::Part { cid, pid };
+                let creature = self.creatures.get_mut(cid).ok_or(Error::NoSuchCreature)?;
+                return creature.resolve(&hit, &mut self.rng);
+            }
+            (Path::Hex { hex }, action::Interact) => {
+                return match self.map.enter_object(*hex) {
+                    Some(object) => {
+                        // A door may have opened.
+                        self.remember();
+                        simple(event::ObjectUsed { object })
+                    }
+                    None => simple(event::Nothing),
+                };
+            }
             _ => ()
         }
         if let Some(cid) = action.target.creature() {
             let creature = self.creatures.get_mut(cid).ok_or(Error::NoSuchCreature)?;
-            return creature.resolve(action);
+            return creature.resolve(action, &mut self.rng);
         }
         Err(Error::UnhandledAction)
     }
@@ -295,6 +533,33 @@ impl World {
         events
     }
 
+    // Adds whatever the player can see now to what they know, walls included.
+    fn remember(&mut self) {
+        let visible = self.visible_to(self.player_id);
+        for hex in &visible {
+            for n in hex.neighbors() {
+                if self.map.tiles().get(&n).map_or(false, |t| t.space.blocks_sight()) {
+                    self.known.insert(n);
+                }
+            }
+        }
+        self.known.extend(visible);
+    }
+
+    // Updates where NPC `id` thinks the player is.
+    fn look(&mut self, id: Id<Creature>) {
+        let player = self.map.creatures().get(&self.player_id).cloned();
+        let at = self.map.creatures().get(&id).cloned();
+        let seen = self.sees(id, self.player_id);
+        let npc = some_or!(self.creatures.get_mut(id).and_then(|c| c.npc_mut()), return);
+        if seen {
+            npc.last_seen = player;
+        } else if npc.last_seen.is_some() && npc.last_seen == at {
+            // Got there and nobody's home.
+            npc.last_seen = None;
+        }
+    }
+
     fn update_npc_plans(&mut self) {
         let ids: Vec<Id<Creature>> = self.creatures.keys().cloned().collect();
         for id in ids {
@@ -321,8 +586,30 @@ impl World {
                 out.push(Path::Part { cid, pid })
             }
         }
+        for (hex, _) in self.hex_entities() {
+            out.push(Path::Hex { hex });
+        }
         out
     }
+
+    // The lowest id keeps the choice deterministic.
+    fn first_open_part(&self, cid: Id<Creature>) -> Option<Id<Part>> {
+        self.creatures.get(cid)?.open_parts().map(|(id, _)| id).min()
+    }
+}
+
+/// What `World::new` starts with.
+pub const DEFAULT_ENCOUNTER: &str = "pillars";
+
+fn default_encounter() -> String { DEFAULT_ENCOUNTER.into() }
+
+fn part_event(cid: Id<Creature>, pid: Id<Part>, data: EventData) -> Event {
+    Meta {
+        source: Path::World,
+        target: Path::Part { cid, pid },
+        tags: HashSet::new(),
+        data,
+    }
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, TsData)]
@@ -380,4 +667,17 @@ impl Scope {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // `World::new` leans on this never failing.
+    #[test]
+    fn default_encounter_loads() {
+        for seed in 0..20 {
+            assert!(World::from_encounter(seed, None, DEFAULT_ENCOUNTER).is_ok(), "seed {}", seed);
+        }
+    }
+}
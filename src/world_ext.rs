@@ -10,6 +10,8 @@ use crate::{
     creature::Creature,
     id_map::Id,
     entity::Entity,
+    map::{self, Object, Pickup, Space},
+    part::{Part, PartTag},
     error::{Error, Result},
     world::{Scope, World},
     some_or,
@@ -23,6 +25,9 @@ pub trait WorldExt {
     // Mutators
     fn execute_all(&mut self, actions: &[Action]) -> Vec<Event>;
     fn move_creature(&mut self, creature_id: Id<Creature>, to: Hex) -> Vec<Event>;
+    fn shove(&mut self, source: &Path, cid: Id<Creature>, direction: (i32, i32), distance: i32) -> Vec<Event>;
+    fn push(&mut self, source: &Path, cid: Id<Creature>, distance: i32) -> Vec<Event>;
+    fn pull(&mut self, source: &Path, cid: Id<Creature>, distance: i32) -> Vec<Event>;
 }
 
 impl WorldExt for World {
@@ -60,6 +65,7 @@ impl WorldExt for World {
                 let part = creature.parts.get(*pid).ok_or(Error::NoSuchPart)?;
                 Ok(&part.entity)
             }
+            Path::Hex { hex } => self.hex_entity(*hex).ok_or(Error::InvalidTarget),
         }
     }
 
@@ -77,11 +83,7 @@ impl WorldExt for World {
     }
 
     fn move_creature(&mut self, creature_id: Id<Creature>, to: Hex) -> Vec<Event> {
-        let from = match self.map().creatures().get(&creature_id) {
-            Some(h) => h,
-            None => return vec![Event::failed(Error::NoSuchCreature)],
-        };
-        let path = match self.map().path_to(*from, to) {
+        let path = match self.map().path_for(creature_id, to) {
             Ok(p) => p,
             Err(e) => return vec![Event::failed(e)],
         };
@@ -98,17 +100,204 @@ impl WorldExt for World {
                 out.push(Event::failed(Error::Obstructed));
                 return out;
             }
+            let cost = match self.map().footprint_cost(creature_id, *to) {
+                Some(c) => c,
+                None => {
+                    out.push(Event::failed(Error::Obstructed));
+                    return out;
+                }
+            };
             let mut mp_evs = self.execute(&Action {
                 source: Path::World,
                 target: Path::Creature { cid: creature_id },
                 tags: HashSet::from_iter(vec![Tag::NoRender]),
-                data: action::SpendMP { mp: 1 },
+                data: action::SpendMP { mp: cost },
             });
             let failed = Event::is_failure(&mp_evs);
             out.append(&mut mp_evs);
             if failed { return out; }
-            out.append(&mut self.execute(&to_creature(creature_id, action::Move { to: *to })));
+            let mut move_evs = self.execute(&to_creature(creature_id, action::Move { to: *to }));
+            let failed = Event::is_failure(&move_evs);
+            out.append(&mut move_evs);
+            if failed { return out; }
+            for act in terrain_actions(self, creature_id) {
+                out.extend(self.execute(&act));
+            }
+            if self.creatures().get(creature_id).map_or(true, |c| c.dead) { return out; }
         }
         out
     }
-}
\ No newline at end of file
+
+    /// Moves `cid` up to `distance` hexes in `direction` (see `map::direction`).
+    /// Running into something stops it short, and every hex it didn't get to
+    /// go turns into damage for it and whatever it hit; being shoved at a pit
+    /// is a fall instead, which only hurts the one falling.
+    fn shove(&mut self, source: &Path, cid: Id<Creature>, direction: (i32, i32), distance: i32) -> Vec<Event> {
+        let (dx, dy) = direction;
+        let mut out = vec![];
+        for step in 0..distance {
+            let at = some_or!(self.map().creatures().get(&cid), return out);
+            let next = Hex { x: at.x + dx, y: at.y + dy };
+            if self.map().footprint_cost(cid, next).is_none() {
+                let blockers = self.map().footprint(cid).at(next);
+                // Nothing to hit but a drop: it goes over the edge instead.
+                if is_drop(self, cid, &blockers) {
+                    let target = some_or!(first_open_part(self, cid), return out);
+                    out.extend(self.execute(&Action {
+                        source: source.clone(),
+                        target: Path::Part { cid, pid: target },
+                        tags: HashSet::new(),
+                        data: action::Hit { damage: FALL_DAMAGE },
+                    }));
+                    return out;
+                }
+                // Whoever's in the way, if anyone.
+                let other = blockers.iter()
+                    .filter_map(|h| self.map().tiles().get(h)?.creature)
+                    .find(|&c| c != cid);
+                let object = blockers.iter().cloned()
+                    .find(|h| self.map().tiles().get(h)
+                        .and_then(|t| t.object.as_ref())
+                        .map_or(false, |o| o.hp().is_some()));
+                let damage = COLLISION_DAMAGE * (distance - step);
+                let mut targets: Vec<Path> = std::iter::once(cid).chain(other)
+                    .filter_map(|c| Some(Path::Part { cid: c, pid: first_open_part(self, c)? }))
+                    .collect();
+                targets.extend(object.map(|hex| Path::Hex { hex }));
+                let hit: Vec<Action> = targets.into_iter()
+                    .map(|target| Action {
+                        source: source.clone(),
+                        target,
+                        tags: HashSet::new(),
+                        data: action::Hit { damage },
+                    })
+                    .collect();
+                for act in hit {
+                    out.extend(self.execute(&act));
+                }
+                return out;
+            }
+            let mut move_evs = self.execute(&Action {
+                source: source.clone(),
+                target: Path::Creature { cid },
+                tags: HashSet::new(),
+                data: action::Move { to: next },
+            });
+            let failed = Event::is_failure(&move_evs);
+            out.append(&mut move_evs);
+            if failed { return out; }
+            for act in terrain_actions(self, cid) {
+                out.extend(self.execute(&act));
+            }
+            if self.creatures().get(cid).map_or(true, |c| c.dead) { return out; }
+        }
+        out
+    }
+
+    /// Shoves `cid` directly away from `source`.
+    fn push(&mut self, source: &Path, cid: Id<Creature>, distance: i32) -> Vec<Event> {
+        let from = some_or!(source.hex(self), return vec![Event::failed(Error::InvalidTarget)]);
+        let at = some_or!(self.map().creatures().get(&cid), return vec![Event::failed(Error::NoSuchCreature)]);
+        let dir = some_or!(map::direction(from, *at), return vec![Event::failed(Error::InvalidTarget)]);
+        self.shove(source, cid, dir, distance)
+    }
+
+    /// Shoves `cid` toward `source`, stopping next to it rather than crashing
+    /// into it.
+    fn pull(&mut self, source: &Path, cid: Id<Creature>, distance: i32) -> Vec<Event> {
+        let to = some_or!(source.hex(self), return vec![Event::failed(Error::InvalidTarget)]);
+        let &at = some_or!(self.map().creatures().get(&cid), return vec![Event::failed(Error::NoSuchCreature)]);
+        let dir = some_or!(map::direction(at, to), return vec![Event::failed(Error::InvalidTarget)]);
+        let gap = source.creature()
+            .and_then(|sc| self.map().gap(cid, at, sc))
+            .unwrap_or_else(|| at.distance_to(to));
+        let distance = std::cmp::min(distance, gap - 1);
+        self.shove(source, cid, dir, distance)
+    }
+}
+
+const COLLISION_DAMAGE: i32 = 3;
+const FALL_DAMAGE: i32 = 8;
+
+// Whether the only thing stopping a footprint at `hexes` is a pit.
+fn is_drop(world: &World, cid: Id<Creature>, hexes: &[Hex]) -> bool {
+    let tiles = world.map().tiles();
+    let mut pit = false;
+    for hex in hexes {
+        let tile = some_or!(tiles.get(hex), return false);
+        if tile.creature.map_or(false, |c| c != cid) || tile.object.is_some() { return false; }
+        match tile.space {
+            Space::Pit => pit = true,
+            Space::Wall => return false,
+            _ => (),
+        }
+    }
+    pit
+}
+
+// The lowest id keeps the choice deterministic.
+fn first_open_part(world: &World, cid: Id<Creature>) -> Option<Id<Part>> {
+    world.creatures().get(cid)?.open_parts().map(|(id, _)| id).min()
+}
+
+const FIRE_DAMAGE: i32 = 3;
+
+// What the terrain and any objects do to a creature that just stepped onto
+// them.  Big creatures only get burned once however much fire they cover.
+fn terrain_actions(world: &World, cid: Id<Creature>) -> Vec<Action> {
+    let tiles = world.map().tiles();
+    let occupied = world.map().occupied(cid);
+    let mut out = vec![];
+    let fire = occupied.iter().find(|h| tiles.get(h).map_or(false, |t| t.space == Space::Fire));
+    if let (Some(&at), Some(pid)) = (fire, first_open_part(world, cid)) {
+        out.push(Action {
+            source: Path::Hex { hex: at },
+            target: Path::Part { cid, pid },
+            tags: HashSet::new(),
+            data: action::Hit { damage: FIRE_DAMAGE },
+        });
+    }
+    for &hex in &occupied {
+        let object = some_or!(tiles.get(&hex).and_then(|t| t.object.as_ref()), continue);
+        match object {
+            Object::Door { open: false } => (),
+            Object::Pickup { effect } => out.extend(pickup_actions(world, cid, hex, *effect)),
+            _ => continue,
+        }
+        out.push(Action {
+            source: Path::Creature { cid },
+            target: Path::Hex { hex },
+            tags: HashSet::new(),
+            data: action::Interact,
+        });
+    }
+    out
+}
+
+fn pickup_actions(world: &World, cid: Id<Creature>, hex: Hex, effect: Pickup) -> Vec<Action> {
+    let source = Path::Hex { hex };
+    let on_creature = |data| vec![Action {
+        source: source.clone(),
+        target: Path::Creature { cid },
+        tags: HashSet::from_iter(vec![Tag::NoRender]),
+        data,
+    }];
+    match effect {
+        Pickup::GainAP { ap } => on_creature(action::GainAP { ap }),
+        Pickup::GainMP { mp } => on_creature(action::GainMP { mp }),
+        Pickup::Heal { hp } => {
+            let creature = some_or!(world.creatures().get(cid), return vec![]);
+            let mut pids: Vec<_> = creature.parts.iter()
+                .filter(|(_, p)| !p.tags().contains(&PartTag::Broken))
+                .map(|(id, _)| *id)
+                .collect();
+            pids.sort();
+            pids.into_iter().map(|pid| Action {
+                source: source.clone(),
+                target: Path::Part { cid, pid },
+                tags: HashSet::new(),
+                data: action::Heal { hp },
+            }).collect()
+        }
+    }
+}
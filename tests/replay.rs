@@ -0,0 +1,121 @@
+//! Replays of recorded games, to catch unintended changes in the rules.
+//!
+//! Each file in `tests/replays` is a `Replay` that must still run to the same
+//! checksum.  After an intended rules change, re-record them with
+//! `RECORD_REPLAYS=1 cargo test --test replay`.
+
+use std::{fs, path::PathBuf};
+
+use rs_parts_unknown::{
+    action::Path,
+    command::Command,
+    replay::Replay,
+    world::{GameState, World},
+};
+
+const ROUNDS: usize = 8;
+
+// Seeds and encounters for the recorded games.  Each plays out the same
+// whichever way the hex crate breaks ties in lines, so the checksums only pin
+// down the rules here.
+const GAMES: &[(&str, u64, &str)] = &[
+    ("pillars_1", 1, "pillars"),
+    ("lone_2", 2, "lone"),
+    ("pair_3", 3, "pair"),
+    ("pack_5", 5, "pack"),
+];
+
+fn corpus_dir() -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join("tests").join("replays")
+}
+
+// Plays a simple game: each round, walk toward the nearest enemy, play
+// whatever cards will go on whatever they'll hit, then end the turn.
+fn play(seed: u64, encounter: &str) -> World {
+    let mut world = World::from_encounter(seed, None, encounter).unwrap();
+    for _ in 0..ROUNDS {
+        if world.state() != GameState::Play { break; }
+        if let Some(to) = approach(&world) {
+            world.apply(Command::MovePlayer { to });
+        }
+        let mut hand_ix = 0;
+        loop {
+            let hand_len = world.creatures().get(world.player_id()).unwrap().hand.len();
+            if hand_ix >= hand_len || world.state() != GameState::Play { break; }
+            match first_play(&world, hand_ix) {
+                Some(command) => { world.apply(command); }
+                None => hand_ix += 1,
+            }
+        }
+        if world.state() != GameState::Play { break; }
+        world.apply(Command::EndTurn);
+    }
+    world
+}
+
+fn approach(world: &World) -> Option<hex::Hex> {
+    let player_id = world.player_id();
+    let mp = world.creatures().get(player_id)?.cur_mp;
+    let map = world.map();
+    let enemies: Vec<hex::Hex> = map.creatures().iter()
+        .filter(|(&id, _)| id != player_id)
+        .map(|(_, &h)| h)
+        .collect();
+    let mut range: Vec<hex::Hex> = map.range_from(player_id, mp, false).into_iter().collect();
+    range.sort_by_key(|h| (h.y, h.x));
+    range.into_iter()
+        .filter(|&to| world.validate(&Command::MovePlayer { to }).is_ok())
+        .min_by_key(|h| enemies.iter().map(|e| h.distance_to(*e)).min().unwrap_or(0))
+}
+
+// The first single-target play of card `hand_ix` that the world accepts.
+fn first_play(world: &World, hand_ix: usize) -> Option<Command> {
+    let mut targets = vec![Path::World];
+    for (&cid, creature) in world.creatures().iter() {
+        targets.push(Path::Creature { cid });
+        for &pid in creature.parts.keys() {
+            targets.push(Path::Part { cid, pid });
+        }
+    }
+    let mut hexes: Vec<hex::Hex> = world.map().tiles().keys().cloned().collect();
+    hexes.sort_by_key(|h| (h.y, h.x));
+    targets.extend(hexes.into_iter().map(|hex| Path::Hex { hex }));
+    targets.into_iter()
+        .map(|t| Command::PlayCard { hand_ix, targets: vec![t] })
+        .find(|c| world.validate(c).is_ok())
+}
+
+#[test]
+fn games_replay_to_the_same_checksum() {
+    for &(_, seed, encounter) in GAMES {
+        let world = play(seed, encounter);
+        let json = Replay::new(&world).to_json().unwrap();
+        let rerun = Replay::parse(&json).and_then(|r| r.run()).unwrap();
+        assert_eq!(rerun.checksum(), world.checksum(), "{} {}", encounter, seed);
+    }
+}
+
+#[test]
+fn recorded_replays_still_match() {
+    let dir = corpus_dir();
+    if std::env::var_os("RECORD_REPLAYS").is_some() {
+        fs::create_dir_all(&dir).unwrap();
+        for &(name, seed, encounter) in GAMES {
+            let json = Replay::new(&play(seed, encounter)).to_json().unwrap();
+            fs::write(dir.join(format!("{}.json", name)), json).unwrap();
+        }
+    }
+    let entries = fs::read_dir(&dir)
+        .unwrap_or_else(|e| panic!("can't read {:?} ({}); record replays with RECORD_REPLAYS=1", dir, e));
+    let mut files: Vec<PathBuf> = entries
+        .map(|e| e.unwrap().path())
+        .filter(|p| p.extension().map_or(false, |e| e == "json"))
+        .collect();
+    files.sort();
+    assert!(!files.is_empty(), "no replays in {:?}; record them with RECORD_REPLAYS=1", dir);
+    for file in files {
+        let data = fs::read_to_string(&file).unwrap();
+        let result = Replay::parse(&data).and_then(|r| r.run());
+        assert!(result.is_ok(), "{:?}: {:?}", file, result.err());
+    }
+}
@@ -0,0 +1,104 @@
+//! Smoke tests for driving a `World` through commands natively.
+
+use rs_parts_unknown::{
+    action::{Event, EventData},
+    command::Command,
+    creature::Creature,
+    id_map::Id,
+    library,
+    map::Space,
+    world::{self, GameState, World},
+};
+
+#[test]
+fn new_world_is_playable() {
+    for seed in 0..10 {
+        let world = World::new(seed);
+        assert_eq!(world.state(), GameState::Play);
+        let player = world.player();
+        assert!(!player.hand.is_empty());
+        assert!(world.known().contains(world.map().creatures().get(&world.player_id()).unwrap()));
+    }
+}
+
+#[test]
+fn generated_encounters_load() {
+    for encounter in &["lone", "pair", "pack"] {
+        for seed in 0..30 {
+            let world = World::from_encounter(seed, None, encounter);
+            assert!(world.is_ok(), "{} {}: {:?}", encounter, seed, world.err());
+        }
+    }
+}
+
+#[test]
+fn debug_player_is_playable() {
+    let player = library::player::debug_player();
+    let world = World::from_encounter(1, Some(player), world::DEFAULT_ENCOUNTER).unwrap();
+    assert_eq!(world.state(), GameState::Play);
+    assert!(world.player().parts.values().any(|p| p.cards.len() > 3));
+}
+
+#[test]
+fn moves_spend_mp() {
+    let mut world = World::new(1);
+    let player_id = world.player_id();
+    let &from = world.map().creatures().get(&player_id).unwrap();
+    let mp = world.player().cur_mp;
+    let to = from.neighbors()
+        .find(|&h| world.validate(&Command::MovePlayer { to: h }).is_ok())
+        .unwrap();
+    let cost = world.map().tiles()[&to].space.cost().unwrap();
+    let events = world.apply(Command::MovePlayer { to });
+    assert!(!Event::is_failure(&events), "{:?}", events);
+    assert_eq!(world.map().creatures().get(&player_id), Some(&to));
+    assert_eq!(world.player().cur_mp, mp - cost);
+}
+
+#[test]
+fn bad_commands_fail_cleanly() {
+    let mut world = World::new(1);
+    let wall = *world.map().tiles().iter()
+        .find(|(_, t)| t.space == Space::Wall)
+        .unwrap().0;
+    let before = world.checksum();
+    assert!(Event::is_failure(&world.apply(Command::MovePlayer { to: wall })));
+    assert!(Event::is_failure(&world.apply(Command::PlayCard { hand_ix: 99, targets: vec![] })));
+    // Rejected commands change nothing, not even the replay log.
+    assert_eq!(world.checksum(), before);
+    assert!(world.log().is_empty());
+}
+
+#[test]
+fn turns_go_around() {
+    let mut world = World::new(2);
+    let player_id = world.player_id();
+    let mut rounds = 0;
+    for _ in 0..5 {
+        if world.state() != GameState::Play { break; }
+        let events = world.apply(Command::EndTurn);
+        // Every NPC still standing did something on its turn.
+        for (&cid, creature) in world.creatures().iter() {
+            if cid == player_id || creature.dead { continue; }
+            assert!(events.iter().any(|e| acted(e, cid)), "{:?} sat out: {:?}", cid, events);
+        }
+        if world.state() != GameState::Play { break; }
+        // And then it's the player's turn again, fully refilled.
+        assert!(world.validate(&Command::EndTurn).is_ok());
+        let player = world.player();
+        assert_eq!(player.cur_ap, player.max_ap());
+        assert_eq!(player.cur_mp, player.max_mp());
+        rounds += 1;
+    }
+    assert!(rounds > 0, "the player never got another turn");
+}
+
+// Moving, paying for its intent, or anything the intent did or reported.
+fn acted(event: &Event, cid: Id<Creature>) -> bool {
+    if event.source.creature() == Some(cid) { return true; }
+    event.target.creature() == Some(cid) && match event.data {
+        EventData::Moved { .. } | EventData::FloatText { .. } => true,
+        EventData::ChangeAP { delta } => delta < 0,
+        _ => false,
+    }
+}
@@ -21,7 +21,8 @@ pub fn ts_data_derive(input: TokenStream) -> TokenStream {
     let tokens = match derive_impl(ast) {
         Ok(output) => {
             quote! {
-                #[wasm_bindgen(typescript_custom_section)]
+                #[cfg(feature = "wasm")]
+                #[wasm_bindgen::prelude::wasm_bindgen(typescript_custom_section)]
                 const TS_APPEND: &'static str = #output;
             }
         }
@@ -141,15 +142,22 @@ impl TranslateType {
             "Entity" => (),
             "Event" => (),
             "Hex" => (),
+            "HexShape" => (),
             "Intent" => (),
             "IntentKind" => (),
+            "Joint" => (),
             "Motion" => (),
             "MotionKind" => (),
+            "Movement" => (),
             "NPC" => (),
+            "Object" => (),
             "Part" => (),
             "PartTag" => (),
             "Path" => (),
+            "Pickup" => (),
             "Range" => (),
+            "Reward" => (),
+            "Socket" => (),
             "Space" => (),
             "Status" => (),
             "Tag" => (),
@@ -230,7 +238,7 @@ impl TranslateType {
                 }
             }
             // Native types
-            "i32" => { self.push_str("number"); }
+            "i32" | "usize" => { self.push_str("number"); }
             "String" => { self.push_str("string"); }
             "bool" => { self.push_str("boolean"); }
             // Mangle
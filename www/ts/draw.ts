@@ -1,4 +1,4 @@
-import {Boundary, Hex, Tile} from "../wasm";
+import {Boundary, Hex, Object, Tile} from "../wasm";
 
 export class Draw {
     constructor(private readonly _ctx: CanvasRenderingContext2D) {}
@@ -15,10 +15,15 @@ export class Draw {
         this._pathHex(hex, HEX_SIZE);
         this._ctx.lineWidth = 1.0;
         this._ctx.strokeStyle = "#004000";
-        this._ctx.fillStyle = "#FFFFFF";
         if (tile.space == "Empty") {
             this._ctx.stroke();
         } else {
+            this._ctx.fillStyle = SPACE_COLORS[tile.space];
+            this._ctx.fill();
+        }
+        if (tile.object) {
+            this._pathHex(hex, HEX_SIZE * 0.6);
+            this._ctx.fillStyle = objectColor(tile.object);
             this._ctx.fill();
         }
 
@@ -37,6 +42,16 @@ export class Draw {
         this._ctx.restore();
     }
 
+    effect(hex: Hex) {
+        this._ctx.save();
+        this._pathHex(hex, HEX_SIZE * 0.85);
+        this._ctx.lineWidth = 2.0;
+        this._ctx.strokeStyle = "#FF8000";
+        this._ctx.setLineDash([4, 4]);
+        this._ctx.stroke();
+        this._ctx.restore();
+    }
+
     throb(hex: Hex, tsMillis: DOMHighResTimeStamp) {
         const SCALE_MIN = 1.0;
         const SCALE_MAX = 1.2;
@@ -150,6 +165,21 @@ export class Draw {
 
 const HEX_SIZE = 30;
 
+function objectColor(object: Object): string {
+    if (object.Crate) { return "#A07030"; }
+    if (object.Barricade) { return "#C0C0C0"; }
+    if (object.Door) { return object.Door.open ? "#403020" : "#806040"; }
+    return "#40C040";  // Pickup
+}
+
+const SPACE_COLORS: Record<string, string> = {
+    Wall: "#FFFFFF",
+    Rough: "#6B5A3A",
+    Water: "#2050A0",
+    Pit: "#202020",
+    Fire: "#C04010",
+};
+
 export function hexToPixel(hex: Hex): DOMPointReadOnly {
     const x = HEX_SIZE * 3/2 * hex.x;
     const y = HEX_SIZE * (Math.sqrt(3)/2 * hex.x + Math.sqrt(3) * hex.y);
@@ -102,6 +102,12 @@ export class GameBoard implements GameBoard.View {
         for (let [hex, tile] of this._cache.tiles) {
             this._draw.tile(hex, tile);
         }
+        for (let hex of this._cache.remembered) {
+            this._draw.shade(hex);
+        }
+        for (let hex of this._cache.effects) {
+            this._draw.effect(hex);
+        }
 
         for (let [id, pos] of this._creaturePos) {
             let text = "X";
@@ -138,6 +144,9 @@ export class GameBoard implements GameBoard.View {
         for (let hex of this._highlightHexes(hi.throb)) {
             this._draw.throb(hex, this._tsMillis);
         }
+        for (let hex of hi.area) {
+            this._draw.throb(hex, this._tsMillis);
+        }
     }
 
     private _highlightHexes(tracker: Readonly<Highlight.Tracker>): Hex[] {
@@ -191,6 +200,10 @@ export namespace GameBoard {
 
 class WorldCache {
     tiles: [Hex, Tile][];
+    // Tiles the player has seen before but can't see now.
+    remembered: Hex[] = [];
+    // Tiles with statuses on them.
+    effects: Hex[] = [];
     creatures: Map<Id<Creature>, Creature>= new Map();
     creatureHex: Map<Id<Creature>, Hex> = new Map();
     playerId: Id<Creature>;
@@ -202,6 +215,16 @@ class WorldCache {
         for (let [hex, tile] of this.tiles) {
             this._tileMap.set(JSON.stringify(hex), tile);
         }
+        const visible = new Set(world.getVisible().map((hex) => JSON.stringify(hex)));
+        for (let [hex, tile] of this.tiles) {
+            // Walls are never in sight themselves.
+            if (tile.space != "Wall" && !visible.has(JSON.stringify(hex))) {
+                this.remembered.push(hex);
+            }
+        }
+        for (let [hex, _] of world.getHexEntities()) {
+            this.effects.push(hex);
+        }
         for (let [id, hex] of world.getCreatureMap()) {
             this.creatureHex.set(id, hex);
             this.creatures.set(id, world.getCreature(id)!);
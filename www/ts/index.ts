@@ -1,7 +1,7 @@
 import {enableAllPlugins} from "immer";
 
 import {Game} from "./game";
-import {LevelState} from "./states/level";
+import {LevelState, loadRun} from "./states/level";
 import {TitleState} from "./states/title";
 
 
@@ -10,7 +10,7 @@ enableAllPlugins();
 function main() {
     const game = new Game();
     //game.stack.push(new TitleState());
-    game.stack.push(new LevelState());
+    game.stack.push(new LevelState(loadRun()));
 }
 
 main();
\ No newline at end of file
@@ -12,6 +12,7 @@ export class Highlight {
     throb = new Highlight.Tracker();
     range: wasm.Boundary[] = [];
     shade: wasm.Hex[] = [];
+    area: wasm.Hex[] = [];
 }
 export namespace Highlight {
     export class Tracker {
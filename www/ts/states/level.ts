@@ -10,22 +10,27 @@ import {Focus} from "../stack/focus";
 import {Highlight} from "../stack/highlight";
 
 import {FloatText} from "../../tsx/float";
+import {TitleState} from "./title";
 
 export class LevelState extends State {
     // These live outside of the stack data so they're not unwound by sub-state pops.
     private _world!: wasm.World;
     private _board!: GameBoard;
 
+    constructor(private _run: wasm.Run) { super(); }
+
     onPushed() {
         const canvas = document.getElementById("mainCanvas") as HTMLCanvasElement;
-        this._world = new wasm.World();
+        this._world = loadWorld(this._run);
         this._world.setTracer(new ConsoleTracer());
         this._board = new GameBoard(canvas, this._world, this.stack.boardListener(), this.stack.data);
         const update = this._updateWorld.bind(this);
         const getWorld = () => { return this._world; }
         const getBoard = () => { return this._board; }
+        const getRun = () => { return this._run; }
+        const advance = this._advance.bind(this);
         this.update(draft => {
-            draft.build(LevelState.Data, getWorld, getBoard, update);
+            draft.build(LevelState.Data, getWorld, getBoard, getRun, update, advance);
         });
     }
 
@@ -62,6 +67,7 @@ export class LevelState extends State {
         const data = this.stack.data.get(LevelState.Data)!;
         data.board.stop();
         data.world.free();
+        data.run.free();
     }
 
     onTileEntered(hex: Hex) {
@@ -85,10 +91,28 @@ export class LevelState extends State {
     private _updateWorld(newWorld: wasm.World) {
         this._world.free();
         this._world = newWorld;
+        saveWorld(this._world);
         this._board.updateWorld(this._world);
         this.update(draft => {});
     }
 
+    // Leaves a finished encounter: on to the next one if the run goes on,
+    // back to the title otherwise.
+    private _advance() {
+        const next = this._run.finishEncounter(this._world);
+        window.localStorage.removeItem(SAVE_KEY);
+        const stack = window.game.stack;
+        stack.pop();  // the game over screen
+        if (next.state() == "InProgress") {
+            saveRun(next);
+            stack.swap(new LevelState(next));
+        } else {
+            window.localStorage.removeItem(RUN_KEY);
+            next.free();
+            stack.swap(new TitleState());
+        }
+    }
+
     private _selectCreature(draft: Stack.Data, id: Id<wasm.Creature>) {
         const world = this.stack.data.get(LevelState.Data)!.world;
         const highlight = draft.build(Highlight);
@@ -113,11 +137,14 @@ export namespace LevelState {
         constructor(
             private _getWorld: () => wasm.World,
             private _getBoard: () => GameBoard,
+            private _getRun: () => wasm.Run,
             public updateWorld: (newWorld: wasm.World) => void,
+            public advance: () => void,
         ) {}
 
         get world(): wasm.World { return this._getWorld(); }
         get board(): GameBoard { return this._getBoard(); }
+        get run(): wasm.Run { return this._getRun(); }
 
         creatureAt(hex: wasm.Hex): wasm.Creature | undefined {
             const tile = this.world.getTile(hex);
@@ -133,9 +160,11 @@ export namespace LevelState {
             }
             let cid = pathCreature(event.target);
             if (cid == undefined) { return undefined; }
-            let pos = this.board.creatureCoords(cid)!;
+            // Nothing to show for creatures out of sight.
+            let creature = this.world.getCreature(cid);
+            let pos = this.board.creatureCoords(cid);
+            if (!creature || !pos) { return undefined; }
             pos = new DOMPoint(pos.x, pos.y);  // clone
-            let creature = this.world.getCreature(cid)!;
 
             let pid = pathPart(event.target);
             let part: wasm.Part | undefined;
@@ -230,6 +259,53 @@ class BufferTracer implements wasm.Tracer {
     }
 }
 
+const SAVE_KEY = "partsUnknownSave";
+const RUN_KEY = "partsUnknownRun";
+
+export function loadRun(): wasm.Run {
+    const saved = window.localStorage.getItem(RUN_KEY);
+    if (saved) {
+        try {
+            return wasm.Run.load(saved);
+        } catch (e) {
+            console.error("Discarding unloadable run:", e);
+            window.localStorage.removeItem(RUN_KEY);
+        }
+    }
+    // A saved fight from some other run is no use now.
+    window.localStorage.removeItem(SAVE_KEY);
+    const seed = Math.floor(Math.random() * 0xFFFFFFFF);
+    console.log("Run seed:", seed);
+    const run = new wasm.Run(seed);
+    saveRun(run);
+    return run;
+}
+
+function saveRun(run: wasm.Run) {
+    window.localStorage.setItem(RUN_KEY, run.save());
+}
+
+function loadWorld(run: wasm.Run): wasm.World {
+    const saved = window.localStorage.getItem(SAVE_KEY);
+    if (saved) {
+        try {
+            return wasm.World.load(saved);
+        } catch (e) {
+            console.error("Discarding unloadable save:", e);
+            window.localStorage.removeItem(SAVE_KEY);
+        }
+    }
+    return run.startEncounter();
+}
+
+function saveWorld(world: wasm.World) {
+    if (world.state() == "Play") {
+        window.localStorage.setItem(SAVE_KEY, world.save());
+    } else {
+        window.localStorage.removeItem(SAVE_KEY);
+    }
+}
+
 function delta(value: number): [string, string] /* text, color */ {
     const sign = value < 0 ? "-" : "+";
     const color = value < 0 ? "#FF0000" : "#00FF00";
@@ -14,6 +14,11 @@ import {UpdateState} from "./update";
 
 export class PlayCardState extends State {
     private _inPlay?: wasm.InPlay;
+    private _card?: wasm.Card;
+    // Highlights for the current targeting step, cleared when the next step
+    // starts.
+    private _stepCreatures: wasm.Creature[] = [];
+    private _stepParts: wasm.Part[] = [];
     constructor(
         private _creatureId: Id<wasm.Creature>,
         private _handIx: number,
@@ -26,40 +31,53 @@ export class PlayCardState extends State {
         if (this._handIx >= creature.hand.length) {
             throw `Invalid hand index ${this._handIx}`;
         }
-        const card = creature.hand[this._handIx];
+        this._card = creature.hand[this._handIx];
         
         this._inPlay = world.startPlay(this._creatureId, this._handIx);
         if (!this._inPlay) {
             throw `Card did not start play`;
         }
-        const targetSpec = this._inPlay.getTargetSpec();
+        this._startStep();
+    }
+
+    private _startStep() {
+        const world = this.stack.data.get(LevelState.Data)!.world;
+        const targetSpec = this._inPlay!.getTargetSpec();
         if (targetSpec.None) {
             // TASK: preview, confirm
-            const [nextWorld, events] = world.finishPlay(this._inPlay!, {World: {}});
-            this._inPlay = undefined;
-            this.stack.swap(new UpdateState(events, nextWorld));
+            this._playOnTarget({World: {}});
             return;
         }
-        const range = this._inPlay.range(world);
+        const range = this._inPlay!.range(world);
         const targetCreatures: wasm.Creature[] = [];
         const targetParts: wasm.Part[] = [];
         for (let creature of world.getCreatures()) {
             const target = creatureToTarget(creature);
-            if (this._inPlay.targetValid(world, target)) {
+            if (this._inPlay!.targetValid(world, target)) {
                 targetCreatures.push(creature);
             }
             for (let part of creature.parts.values()) {
                 const target = partToTarget(part);
-                if (this._inPlay.targetValid(world, target)) {
+                if (this._inPlay!.targetValid(world, target)) {
                     targetParts.push(part);
                 }
             }
         }
+        const oldCreatures = this._stepCreatures;
+        const oldParts = this._stepParts;
+        this._stepCreatures = targetCreatures;
+        this._stepParts = targetParts;
         this.update((draft) => {
-            draft.set(PlayCardState.UI, card, (target) => this._playOnTarget(target), () => this._inPlay);
+            draft.set(PlayCardState.UI, this._card!, (target) => this._playOnTarget(target), () => this._inPlay);
             const hi = draft.build(Highlight);
             hi.throb.clear();
             hi.range = wasm.findBoundary(range);
+            for (let creature of oldCreatures) {
+                hi.static.creatures.dec(creature.id);
+            }
+            for (let part of oldParts) {
+                hi.static.mutPartsFor(part.creatureId).dec(part.id);
+            }
             for (let creature of targetCreatures) {
                 hi.static.creatures.inc(creature.id);
             }
@@ -90,12 +108,23 @@ export class PlayCardState extends State {
     onTileEntered(hex: Hex) {
         const level = this.stack.data.get(LevelState.Data)!;
         const world = level.world;
+        const spec = this._inPlay!.getTargetSpec();
+        if (spec.Hex || spec.Object) {
+            const target = {Hex: {hex}};
+            if (!this._inPlay!.targetValid(world, target)) { return; }
+            const area = this._inPlay!.targetArea(world, target);
+            const events = this._inPlay!.preview(world, target);
+            this.update(draft => {
+                draft.build(Highlight).area = area;
+                draft.build(Preview).setEvents(level, events);
+            });
+            return;
+        }
         const creature = level.creatureAt(hex);
-        if (!creature) { return; }  // TASK: hex targeting
+        if (!creature) { return; }
         if (!this._canTargetCreature(creature)) { return; }
 
         const events: wasm.Event[] = [];
-        const spec = this._inPlay!.getTargetSpec();
         if (spec.Creature) {
             const target = creatureToTarget(level.creatureAt(hex)!);
             events.push(...this._inPlay!.preview(world, target));
@@ -113,6 +142,7 @@ export class PlayCardState extends State {
             if (creature) {
                 draft.build(Highlight).throb.creatures.dec(creature.id);
             }
+            draft.build(Highlight).area = [];
             draft.build(Preview).setEvents(level, []);
         });
     }
@@ -134,6 +164,9 @@ export class PlayCardState extends State {
             if (!creature) { return; }
             const target = creatureToTarget(creature);
             this._playOnTarget(target);
+        } else if (spec.Hex || spec.Object) {
+            this.update(draft => { draft.build(Highlight).area = []; });
+            this._playOnTarget({Hex: {hex}});
         } else {
             throw "Unknown target spec!";
         }
@@ -143,7 +176,11 @@ export class PlayCardState extends State {
         const level = this.stack.data.get(LevelState.Data)!;
         const creature = level.creatureAt(hex);
         if (creature && this._canTargetCreature(creature)) { return true; }
-        // TASK: hex targeting
+        const spec = this._inPlay!.getTargetSpec();
+        if (spec.Hex || spec.Object) {
+            const world = level.world;
+            return this._inPlay!.targetValid(world, {Hex: {hex}});
+        }
         return false;
     }
 
@@ -173,6 +210,11 @@ export class PlayCardState extends State {
         if (!this._inPlay!.targetValid(world, target)) {
             return;
         }
+        if (this._inPlay!.remaining > 1) {
+            this._inPlay!.pick(world, target);
+            this._startStep();
+            return;
+        }
         const [nextWorld, events] = world.finishPlay(this._inPlay!, target);
         this._inPlay = undefined;
         
@@ -1,12 +1,12 @@
 import {immerable} from "immer";
 
 import {Stack, State} from "../stack";
-import {LevelState} from "./level";
+import {LevelState, loadRun} from "./level";
 
 export class TitleState extends State {
     onActivated() {
         this.update(draft => {
-            draft.build(TitleState.UI, () => window.game.stack.swap(new LevelState()));
+            draft.build(TitleState.UI, () => window.game.stack.swap(new LevelState(loadRun())));
         });
     }
 }
@@ -16,6 +16,41 @@ export const CARDS: Readonly<CardData> = Object.freeze({
             <Expose/> this part to <Guard/> another until your next turn.
         </span>),
     },
+    "Caltrops": {
+        icon: "foot-trip.svg",
+        text: (props) => (<span>
+            Scatter spikes on an empty hex up to 2 away; the next creature to
+            step there takes <Scaled data={props.ui.damage}/> damage.
+        </span>),
+    },
+    "Firebomb": {
+        icon: "thrown-charcoal.svg",
+        text: (props) => (<span>
+            Set the ground around a visible hex burning for 3 turns; anyone
+            entering or standing in it takes <Scaled data={props.ui.damage}/>
+            {' '}damage.
+        </span>),
+    },
+    "Flurry": {
+        icon: "punch.svg",
+        text: (props) => (<span>
+            <Attack/> two different parts on adjacent enemies for
+            {' '}<Scaled data={props.ui.damage}/>
+            {' '}<Tags tags={props.ui.tags} skip={["Attack"]}/> damage each.
+        </span>),
+    },
+    "Grab": {
+        icon: "grab.svg",
+        text: () => (<span>
+            Pull a visible creature up to 2 hexes toward you.
+        </span>),
+    },
+    "Leap": {
+        icon: "leg.svg",
+        text: () => (<span>
+            Move to an open space up to 2 hexes away.
+        </span>),
+    },
     "Punch": {
         icon: "punch.svg",
         text: (props) => (<span>
@@ -37,12 +72,44 @@ export const CARDS: Readonly<CardData> = Object.freeze({
             {' '}<Scaled data={props.ui.heal}/> HP.
         </span>),
     },
+    "Shockwave": {
+        icon: "convergence-target.svg",
+        text: (props) => (<span>
+            <Attack/> every exposed part of every creature around a visible
+            hex for <Scaled data={props.ui.damage}/>
+            {' '}<Tags tags={props.ui.tags} skip={["Attack"]}/> damage.
+        </span>),
+    },
+    "Shove": {
+        icon: "push.svg",
+        text: () => (<span>
+            Push an adjacent creature up to 2 hexes away; if it hits something,
+            both take 3 damage for each hex left. Shoved into a pit, it falls
+            and takes 8.
+        </span>),
+    },
+    "Spit": {
+        icon: "spitter.svg",
+        text: (props) => (<span>
+            <Attack/> every exposed part in a line 4 hexes long for
+            {' '}<Scaled data={props.ui.damage}/>
+            {' '}<Tags tags={props.ui.tags} skip={["Attack"]}/> damage.
+        </span>),
+    },
     "Stagger": {
         icon: "foot-trip.svg",
         text: () => (<span>
             <Expose/> a random part on an adjacent enemy until end of turn.
         </span>),
     },
+    "Sweep": {
+        icon: "sweep.svg",
+        text: (props) => (<span>
+            <Attack/> every exposed part in a cone 2 hexes deep for
+            {' '}<Scaled data={props.ui.damage}/>
+            {' '}<Tags tags={props.ui.tags} skip={["Attack"]}/> damage.
+        </span>),
+    },
     "Throw Debris": {
         icon: "thrown-charcoal.svg",
         text: (props) => (<span>
@@ -97,6 +97,7 @@ function PartStats(props: {
             <span>{tagIcons}</span>
         </div>
         <div style={hpStyle}>HP: {props.part.curHp + hpDelta}/{props.part.maxHp}</div>
+        {props.part.power != 0 ? <div>Power: {props.part.power}</div> : null}
         <Entity entity={props.part.entity}/>
     </div>
 }
@@ -161,9 +162,9 @@ export function CreatureIntent(props: {
     let intent: JSX.Element = <span>???</span>;
     let kind;
     if (kind = props.intent.kind.Attack) {
-        let intentIcon;
-        switch (kind.range) {
-            case "Melee": intentIcon = "icons/punch.svg";
+        let intentIcon = "icons/punch.svg";
+        if (kind.range.Ranged) {
+            intentIcon = "icons/thrown-charcoal.svg";
         }
         intent = <span><img src={intentIcon} className="attackIcon"></img>{kind.damage}</span>
     }
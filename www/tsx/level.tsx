@@ -49,13 +49,66 @@ export function Level(props: {}): JSX.Element {
 
 function GameOver(props: {}): JSX.Element | null {
     const data = React.useContext(StackData);
+    const world = React.useContext(WorldContext);
+    const level = data.get(LevelState.Data)!;
     const state = data.get(GameOverState.UI)?.state;
     if (!state) { return null; }
+    const lastStage = level.run.stage + 1 >= level.run.stageCount;
     let text: string;
+    let next: string;
     switch (state) {
-        case "Lost": text = "You Lost!"; break;
-        case "Won": text = "You Won!"; break;
-        default: text = `ERROR: ${state}`;
+        case "Lost":
+            text = `You Lost! (encounter ${level.run.stage + 1} of ${level.run.stageCount})`;
+            next = "Back to Title";
+            break;
+        case "Won":
+            text = lastStage ? "Run Complete!" : "You Won!";
+            next = lastStage ? "Back to Title" : "Next Encounter";
+            break;
+        default:
+            text = `ERROR: ${state}`;
+            next = "Back to Title";
     }
-    return <div className="gameOver uibox">{text}</div>;
+    return (<div className="gameOver uibox">
+        {text}
+        {state == "Won" ? <Rewards/> : null}
+        <button onClick={() => level.advance()}>{next}</button>
+        <button onClick={() => downloadReplay(world)}>Download Replay</button>
+    </div>);
+}
+
+function Rewards(props: {}): JSX.Element | null {
+    const data = React.useContext(StackData);
+    const world = React.useContext(WorldContext);
+    const rewards = world.getRewards();
+    if (rewards.length == 0) { return null; }
+    const player = world.getCreature(world.playerId)!;
+    const take = (reward: wasm.Reward, socket: wasm.Socket) => {
+        const [next, _events] = world.harvest(reward.cid, reward.pid, socket.pid, socket.joint);
+        data.get(LevelState.Data)!.updateWorld(next);
+    };
+    const buttons = [];
+    for (let reward of rewards) {
+        const creature = world.getCreature(reward.cid)!;
+        const part = creature.parts.get(reward.pid)!;
+        for (let socket of reward.sockets) {
+            const parent = player.parts.get(socket.pid)!;
+            let label = `Take ${creature.name}'s ${part.name} onto your ${parent.name}`;
+            if (socket.replaces !== undefined) {
+                label += ` (replacing ${player.parts.get(socket.replaces)!.name})`;
+            }
+            buttons.push(<button key={`${reward.cid}:${reward.pid}:${socket.pid}:${socket.joint}`}
+                onClick={() => take(reward, socket)}>{label}</button>);
+        }
+    }
+    return <div className="rewards">{buttons}</div>;
+}
+
+function downloadReplay(world: wasm.World) {
+    const blob = new Blob([world.replay()], {type: "application/json"});
+    const link = document.createElement("a");
+    link.href = URL.createObjectURL(blob);
+    link.download = "parts-unknown-replay.json";
+    link.click();
+    URL.revokeObjectURL(link.href);
 }
\ No newline at end of file
@@ -22,6 +22,9 @@ export function PlayerControls(props: {}): JSX.Element {
     const play = data.get(PlayCardState.UI);
 
     const movePlayer = () => window.game.stack.push(new MovePlayerState());
+    const level = data.get(LevelState.Data)!;
+    const undo = () => level.updateWorld(world.undo()!);
+    const redo = () => level.updateWorld(world.redo()!);
 
     const hasAp = player.curAp > 0;
     const hasMp = player.curMp > 0;
@@ -44,6 +47,8 @@ export function PlayerControls(props: {}): JSX.Element {
                     {player.draw.length}
                 </div>
                 {baseActive && hasMp && <button onClick={movePlayer}>Move</button>}
+                {baseActive && world.canUndo() && <button onClick={undo}>Undo</button>}
+                {baseActive && world.canRedo() && <button onClick={redo}>Redo</button>}
             </div>
             <div className="bottom">
                 <Hand